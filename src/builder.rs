@@ -0,0 +1,273 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, USER_AGENT};
+
+use crate::{
+    retry::{RateLimit, RateLimiter, RetryPolicy},
+    Client, Error, Result,
+};
+
+/// The default for [`ClientBuilder::base_url`].
+pub const DEFAULT_BASE_URL: &str = "https://api.notion.com/v1/";
+
+/// The default for [`ClientBuilder::notion_version`].
+///
+/// [Versioning](https://developers.notion.com/reference/versioning)
+pub const DEFAULT_NOTION_VERSION: &str = "2022-06-28";
+
+/// Configures a [`Client`].
+///
+/// ```no_run
+/// # fn main() -> notion_rs::Result<()> {
+/// use std::time::Duration;
+///
+/// let client = notion_rs::Client::builder("secret_...")
+///     .base_url("http://localhost:8080/v1/")
+///     .timeout(Duration::from_secs(30))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct ClientBuilder {
+    integration_token: String,
+    base_url: String,
+    notion_version: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: String,
+    default_headers: HeaderMap,
+    http: Option<reqwest::Client>,
+    retry: RetryPolicy,
+    rate_limit: Option<RateLimit>,
+}
+
+impl ClientBuilder {
+    pub(crate) fn new(integration_token: String) -> Self {
+        Self {
+            integration_token,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            notion_version: DEFAULT_NOTION_VERSION.to_string(),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: concat!("notion-rs/", env!("CARGO_PKG_VERSION")).to_string(),
+            default_headers: HeaderMap::new(),
+            http: None,
+            retry: RetryPolicy::default(),
+            rate_limit: Some(RateLimit::default()),
+        }
+    }
+
+    /// The URL every endpoint path is appended to, e.g. a mock server or a proxy.
+    ///
+    /// A trailing `/` is added if missing. Default: [`DEFAULT_BASE_URL`].
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        self.base_url = base_url;
+        self
+    }
+
+    /// The `Notion-Version` header. Default: [`DEFAULT_NOTION_VERSION`].
+    pub fn notion_version(mut self, version: impl Into<String>) -> Self {
+        self.notion_version = version.into();
+        self
+    }
+
+    /// The timeout for each request, from sending it until the body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The timeout for establishing a connection.
+    ///
+    /// Ignored if a client is passed to [`ClientBuilder::http_client`].
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// The `User-Agent` header. Default: `notion-rs/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Headers sent with every request. They can override everything but `Authorization`.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Add a single header sent with every request.
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Use an existing `reqwest` client, e.g. one that is configured with a proxy.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http = Some(client);
+        self
+    }
+
+    /// The policy used to retry rate limited and failed requests. Default: [`RetryPolicy::default`].
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// The client-side rate limit. `None` disables it. Default: [`RateLimit::default`].
+    pub fn rate_limit(mut self, limit: Option<RateLimit>) -> Self {
+        self.rate_limit = limit;
+        self
+    }
+
+    /// # Errors
+    /// - If the user agent, Notion version or integration token are not valid header values.
+    /// - If the rate limit does not allow a positive number of requests per second.
+    /// - If the `reqwest` client cannot be built.
+    pub fn build(self) -> Result<Client> {
+        if let Some(RateLimit {
+            requests_per_second,
+            ..
+        }) = self.rate_limit
+        {
+            // Also rejects NaN, which would never refill the bucket.
+            if requests_per_second.is_nan() || requests_per_second <= 0.0 {
+                return Err(Error::InvalidRateLimit {
+                    requests_per_second,
+                });
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Notion-Version",
+            HeaderValue::from_str(&self.notion_version)?,
+        );
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(USER_AGENT, HeaderValue::from_str(&self.user_agent)?);
+        headers.extend(self.default_headers);
+
+        let mut auth = HeaderValue::from_str(&format!("Bearer {}", self.integration_token))?;
+        auth.set_sensitive(true);
+        headers.insert(reqwest::header::AUTHORIZATION, auth);
+
+        let req = match self.http {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(Client {
+            req,
+            base_url: self.base_url,
+            headers,
+            timeout: self.timeout,
+            retry: self.retry,
+            limiter: self
+                .rate_limit
+                .map(|limit| Arc::new(RateLimiter::new(limit))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::header::{HeaderName, HeaderValue};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::{Client, Error, RateLimit, RetryPolicy};
+
+    fn users() -> serde_json::Value {
+        serde_json::json!({ "object": "list", "results": [] })
+    }
+
+    #[tokio::test]
+    async fn sends_configured_headers() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/users"))
+            .and(header("Notion-Version", "2099-01-01"))
+            .and(header("User-Agent", "my-tool/1.0"))
+            .and(header("X-Trace", "abc"))
+            .and(header("Authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(users()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(format!("{}/v1", server.uri()))
+            .notion_version("2099-01-01")
+            .user_agent("my-tool/1.0")
+            .default_header(
+                HeaderName::from_static("x-trace"),
+                HeaderValue::from_static("abc"),
+            )
+            .http_client(reqwest::Client::new())
+            .build()
+            .unwrap();
+
+        client.list_users().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(users())
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .timeout(Duration::from_millis(50))
+            .retry_policy(RetryPolicy::disabled())
+            .build()
+            .unwrap();
+
+        let err = client.list_users().await.unwrap_err();
+        assert!(matches!(err, Error::Http(err) if err.is_timeout()));
+    }
+
+    #[test]
+    fn rejects_invalid_header_values() {
+        let res = Client::builder("secret").user_agent("bad\nagent").build();
+        assert!(matches!(res, Err(Error::InvalidHeaderValue(_))));
+    }
+
+    #[test]
+    fn rejects_rate_limits_without_requests() {
+        for requests_per_second in [0.0, -1.0, f64::NAN] {
+            let res = Client::builder("secret")
+                .rate_limit(Some(RateLimit {
+                    requests_per_second,
+                    burst: 3,
+                }))
+                .build();
+            assert!(matches!(res, Err(Error::InvalidRateLimit { .. })));
+        }
+    }
+}
@@ -7,34 +7,71 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
+use crate::PageId;
+
 mod block;
 mod color;
+mod comment;
+mod database;
+mod date;
+mod file;
 pub mod parent_object;
 mod property;
 mod rich_text;
+mod unknown;
+mod user;
 
-pub use block::{Block, BlockData};
+pub use block::{Block, BlockData, Heading, LenientBlocks, LinkToPage, Media, SyncedFrom};
+pub use color::Color;
+pub use comment::Comment;
+pub use database::{
+    Database, FormulaConfig, NumberConfig, NumberFormat, PropertyConfig, PropertySchema,
+    RelationConfig, RelationKind, RollupConfig, RollupFunction, SelectConfig, SelectOption,
+    StatusConfig, StatusGroup, UniqueIdConfig,
+};
+pub use date::{Date, DateTime, DateValue};
+pub use file::{CustomEmoji, FileObject, HostedFile, Icon, NamedFile};
+pub use parent_object::Parent;
+pub use property::{
+    ExternalUrl, Formula, Property, PropertyData, PropertyValue, Reference, Rollup, RollupValue,
+    SelectValue, UniqueId, Verification, VerificationState,
+};
+pub use rich_text::{
+    Annotations, Link, Mention, MentionData, RichText, RichTextData, TemplateMention,
+    TemplateMentionDate, TemplateMentionUser,
+};
+pub use user::{Bot, BotOwner, MaybePartialUser, PartialUser, Person, User};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum SortDirection {
     Ascending,
     Descending,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum SortTimestamp {
     LastEditedTime,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct Sort {
     pub direction: SortDirection,
     pub timestamp: SortTimestamp,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Sort {
+    #[must_use]
+    pub const fn last_edited_time(direction: SortDirection) -> Self {
+        Self {
+            direction,
+            timestamp: SortTimestamp::LastEditedTime,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct Filter<'a> {
     /// The value of the property to filter the results by. Possible values for object type include
     /// page or database. Limitation: Currently the only filter allowed is object which will filter
@@ -46,8 +83,28 @@ pub struct Filter<'a> {
     pub property: &'a str,
 }
 
+impl Filter<'static> {
+    /// Only return pages.
+    #[must_use]
+    pub const fn pages() -> Self {
+        Self {
+            value: "page",
+            property: "object",
+        }
+    }
+
+    /// Only return databases.
+    #[must_use]
+    pub const fn databases() -> Self {
+        Self {
+            value: "database",
+            property: "object",
+        }
+    }
+}
+
 #[skip_serializing_none]
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub struct SearchRequest<'a> {
     /// The text that the API compares page and database titles against.
     pub query: Option<&'a str>,
@@ -78,8 +135,25 @@ impl Default for SearchRequest<'_> {
 pub enum Object {
     User(User),
     List(List),
-    Page(Page),
+    Page(Box<Page>),
     Block(Box<Block>),
+    Database(Box<Database>),
+    Comment(Box<Comment>),
+}
+
+impl Object {
+    /// The value of the `object` field, e.g. `"page"`.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::User(_) => "user",
+            Self::List(_) => "list",
+            Self::Page(_) => "page",
+            Self::Block(_) => "block",
+            Self::Database(_) => "database",
+            Self::Comment(_) => "comment",
+        }
+    }
 }
 
 // #[derive(Serialize, Deserialize, Debug)]
@@ -93,24 +167,20 @@ pub enum Object {
 //     block: Block,
 // }
 //
-/// `2023-03-08T18:25:00.000Z`
-type DateTime = String;
-
-/// `2023-03-08`
-type Date = String;
 
 /// [Reference](https://developers.notion.com/reference/page)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Page {
-    /// `be633bf1-dfa0-436d-b259-571129a590e5`
-    pub id: String,
+    pub id: PageId,
+    #[serde(with = "crate::data::date::timestamp")]
     pub created_time: DateTime,
+    #[serde(with = "crate::data::date::timestamp")]
     pub last_edited_time: DateTime,
-    pub created_by: User,
-    pub last_edited_by: User,
-    pub cover: Option<serde_json::Value>,
-    pub icon: Option<serde_json::Value>,
-    pub parent: Option<parent_object::ParentObject>,
+    pub created_by: PartialUser,
+    pub last_edited_by: PartialUser,
+    pub cover: Option<FileObject>,
+    pub icon: Option<Icon>,
+    pub parent: Option<Parent>,
     pub archived: bool,
     pub properties: HashMap<String, property::Property>,
     pub url: String,
@@ -123,15 +193,22 @@ impl Page {
         Some(data)
     }
 
+    /// The title as plain text, made up of all its segments.
+    ///
+    /// Empty if the page has no title.
     #[must_use]
-    pub fn title(&self) -> Option<&TextBlock> {
-        let property::PropertyData::Title(title) = self.property("title")? else {
-            return None;
-        };
-        title.get(0)
+    pub fn title(&self) -> String {
+        self.properties
+            .values()
+            .find_map(|property| property.data.as_title())
+            .unwrap_or_default()
+            .iter()
+            .map(|text| text.plain_text.as_str())
+            .collect()
     }
 }
 
+#[allow(dead_code)]
 #[derive(Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
 enum PropertyType {
@@ -160,26 +237,10 @@ enum PropertyType {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct List {
     pub results: Vec<Object>,
-}
-
-#[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Clone)]
-pub struct User {
-    pub id: String,
-    pub name: Option<String>,
-    pub avatar_url: Option<String>,
-    pub r#type: Option<String>,
-    pub person: Option<Person>,
-}
-
-#[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Clone)]
-pub struct Person {
-    pub email: String,
-}
-
-#[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
-pub struct TextData {
-    pub content: Option<String>,
-    pub link: Option<String>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
 }
 
 // /// <https://developers.notion.com/reference/block>
@@ -209,29 +270,53 @@ pub struct TextData {
 //     Unsupported,
 // }
 
-#[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Hash, Default)]
-#[allow(clippy::struct_excessive_bools)]
-pub struct Annotations {
-    pub bold: bool,
-    pub italic: bool,
-    pub strikethrough: bool,
-    pub underline: bool,
-    pub code: bool,
-    pub color: String,
+/// A result of [`Client::search`](crate::Client::search).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum PageOrDatabase {
+    Page(Box<Page>),
+    Database(Box<Database>),
 }
 
-#[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
-pub struct TextBlock {
-    pub text: TextData,
-    pub annotations: Annotations,
-    pub plain_text: String,
-    pub href: Option<String>,
+impl PageOrDatabase {
+    /// The title as plain text.
+    #[must_use]
+    pub fn title(&self) -> String {
+        match self {
+            Self::Page(page) => page.title(),
+            Self::Database(database) => database.title(),
+        }
+    }
+
+    #[must_use]
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Page(page) => &page.url,
+            Self::Database(database) => &database.url,
+        }
+    }
+
+    #[must_use]
+    pub fn as_page(&self) -> Option<&Page> {
+        match self {
+            Self::Page(page) => Some(page),
+            Self::Database(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_database(&self) -> Option<&Database> {
+        match self {
+            Self::Database(database) => Some(database),
+            Self::Page(_) => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SearchResponse {
     pub object: String,
-    pub results: Vec<Page>,
+    pub results: Vec<PageOrDatabase>,
     pub next_cursor: Option<String>,
     pub has_more: bool,
     pub r#type: String,
@@ -240,6 +325,17 @@ pub struct SearchResponse {
 
 #[cfg(test)]
 mod tests {
+    use crate::data::{Color, Page};
+
+    #[test]
+    fn page_title_joins_segments() {
+        let page: Page = serde_json::from_str(include_str!("../tests/fixtures/page.json")).unwrap();
+        assert_eq!(page.title(), "Tuscan kale");
+
+        let title = page.properties["Name"].data.as_title().unwrap();
+        assert_eq!(title[1].annotations.color, Color::Green);
+    }
+
     // use crate::data::{Annotations, Block, Object, Page, TextBlock, TextData};
 
     // #[test]
@@ -1,10 +1,24 @@
-use crate::data::{color::Color, rich_text::RichText, Object};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    data::{
+        color::Color,
+        date::DateTime,
+        file::{FileObject, Icon},
+        parent_object::Parent,
+        rich_text::RichText,
+        unknown::{self, Tagged},
+        user::PartialUser,
+    },
+    BlockDecodeError, BlockId, DatabaseId, PageId,
+};
 
 /// <https://developers.notion.com/reference/block>
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, Debug, Clone)]
 pub struct Block {
-    pub id: String,
-    pub parent: Option<serde_json::Value>,
+    pub id: BlockId,
+    pub parent: Option<Parent>,
 
     /// TODO: this links to `data`. Is there anyway we can add
     /// `#[serde(rename = "type")]` to the `BlockData` enum?
@@ -15,13 +29,93 @@ pub struct Block {
     #[serde(flatten)]
     pub data: BlockData,
 
-    pub created_time: String,
-    pub created_by: serde_json::Value,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub created_time: DateTime,
+    pub created_by: PartialUser,
 
-    pub last_edited_time: String,
-    pub last_edited_by: serde_json::Value,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub last_edited_time: DateTime,
+    pub last_edited_by: PartialUser,
     pub archived: bool,
     pub has_children: bool,
+
+    /// Not part of the API response. Filled in by
+    /// [`Client::block_tree`](crate::Client::block_tree).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+impl Block {
+    /// The `child_page` block of `page`.
+    pub(crate) fn child_page(page: super::Page) -> Self {
+        Self {
+            data: BlockData::ChildPage {
+                title: page.title(),
+            },
+            id: page.id.into(),
+            r#type: "child_page".to_string(),
+            parent: page.parent,
+            created_time: page.created_time,
+            created_by: page.created_by,
+            last_edited_time: page.last_edited_time,
+            last_edited_by: page.last_edited_by,
+            archived: page.archived,
+            // Pages do not say whether they have content.
+            has_children: true,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Block {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // `data` cannot be flattened: an unknown payload can only be told apart from other
+        // unknown fields by `type`.
+        #[derive(Deserialize)]
+        struct Raw {
+            id: BlockId,
+            parent: Option<Parent>,
+            r#type: String,
+            #[serde(with = "crate::data::date::timestamp")]
+            created_time: DateTime,
+            created_by: PartialUser,
+            #[serde(with = "crate::data::date::timestamp")]
+            last_edited_time: DateTime,
+            last_edited_by: PartialUser,
+            archived: bool,
+            has_children: bool,
+            #[serde(default)]
+            children: Vec<Block>,
+            #[serde(flatten)]
+            rest: serde_json::Map<String, serde_json::Value>,
+        }
+
+        let mut raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            data: unknown::from_payload(&raw.r#type, &mut raw.rest)?,
+            id: raw.id,
+            parent: raw.parent,
+            r#type: raw.r#type,
+            created_time: raw.created_time,
+            created_by: raw.created_by,
+            last_edited_time: raw.last_edited_time,
+            last_edited_by: raw.last_edited_by,
+            archived: raw.archived,
+            has_children: raw.has_children,
+            children: raw.children,
+        })
+    }
+}
+
+/// Children of a block that were decoded one at a time.
+///
+/// Returned by [`Client::block_children_lenient`](crate::Client::block_children_lenient).
+#[derive(Debug, Default)]
+pub struct LenientBlocks {
+    /// The children that decoded, in order.
+    pub blocks: Vec<Block>,
+    /// The children that did not decode, in order.
+    pub failed: Vec<BlockDecodeError>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
@@ -29,25 +123,63 @@ pub struct Heading {
     pub rich_text: Vec<RichText>,
     pub color: Color,
     pub is_toggleable: bool,
+    /// Only when creating toggleable headings. Never filled in responses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<BlockData>,
+}
+
+/// The caption and file of an audio, file, image, PDF or video block.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Media {
+    #[serde(default)]
+    pub caption: Vec<RichText>,
+    #[serde(flatten)]
+    pub file: FileObject,
+    /// Only set for file blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// The original block a synced block copies its content from.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncedFrom {
+    BlockId { block_id: BlockId },
+}
+
+/// The page or database a link to page block points to.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LinkToPage {
+    PageId { page_id: PageId },
+    DatabaseId { database_id: DatabaseId },
 }
 
 /// <https://developers.notion.com/reference/block>
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", remote = "Self")]
 pub enum BlockData {
+    Audio(Media),
     Bookmark {
+        #[serde(default)]
         caption: Vec<RichText>,
         url: String,
     },
-    BreadCrumb,
+    Breadcrumb {},
     BulletedListItem {
         rich_text: Vec<RichText>,
         color: Color,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
     },
     Callout {
-        rich_text: RichText,
-        icon: Object,
+        rich_text: Vec<RichText>,
+        icon: Option<Icon>,
         color: Color,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
     },
     ChildDatabase {
         title: String,
@@ -55,67 +187,211 @@ pub enum BlockData {
     ChildPage {
         title: String,
     },
-    Column,
-    ColumnList,
-    Divider,
+    Code {
+        #[serde(default)]
+        caption: Vec<RichText>,
+        rich_text: Vec<RichText>,
+        /// As shown in the language picker, lowercased, e.g. `rust` or `plain text`.
+        language: String,
+    },
+    Column {
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    /// Must contain at least two columns when created.
+    ColumnList {
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    Divider {},
     Embed {
+        #[serde(default)]
+        caption: Vec<RichText>,
         url: String,
     },
     Equation {
         expression: String,
     },
-    File {
-        caption: Vec<RichText>,
-        r#type: String,
-        /// file object
-        file: Object,
-    },
-
+    File(Media),
     #[serde(rename = "heading_1")]
     Heading1(Heading),
     #[serde(rename = "heading_2")]
     Heading2(Heading),
     #[serde(rename = "heading_3")]
     Heading3(Heading),
-    /// TODO
-    Image,
+    Image(Media),
     LinkPreview {
         url: String,
     },
-    LinkToPage {
-        url: String,
-    },
+    LinkToPage(LinkToPage),
     NumberedListItem {
         rich_text: Vec<RichText>,
         color: Color,
-        #[serde(default)]
-        children: Vec<Block>,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
     },
     Paragraph {
         rich_text: Vec<RichText>,
         color: Color,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    Pdf(Media),
+    Quote {
+        rich_text: Vec<RichText>,
+        color: Color,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    SyncedBlock {
+        /// `None` for the original block, which the copies are synced from.
+        synced_from: Option<SyncedFrom>,
+        /// Only when creating an original block. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    Table {
+        /// Cannot be changed after the table is created.
+        table_width: u32,
+        has_column_header: bool,
+        has_row_header: bool,
+        /// The rows, only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    TableOfContents {
+        color: Color,
+    },
+    TableRow {
+        /// One entry per column, each made up of rich text.
+        cells: Vec<Vec<RichText>>,
+    },
+    /// Deprecated by Notion. Can no longer be created.
+    Template {
+        rich_text: Vec<RichText>,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    ToDo {
+        rich_text: Vec<RichText>,
         #[serde(default)]
-        children: Vec<Block>,
+        checked: bool,
+        color: Color,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
     },
-    Pdf {
-        caption: Vec<RichText>,
+    Toggle {
+        rich_text: Vec<RichText>,
+        color: Color,
+        /// Only when creating blocks. Never filled in responses.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        children: Vec<BlockData>,
+    },
+    Unsupported {},
+    Video(Media),
+    /// A block type this crate does not know about yet. Serializes back to the same payload.
+    #[serde(skip)]
+    Unknown {
         r#type: String,
-        /// file object
-        file: Object,
-    },
-    Quote,
-    SyncedBlock,
-    Table,
-    TableOfContents,
-    TableRow,
-    Template,
-    ToDo,
-    Toggle,
-    Unsupported,
-    Video,
+        raw: serde_json::Value,
+    },
+}
+
+impl Tagged for BlockData {
+    const NAME: &'static str = "block";
+
+    fn known_types() -> &'static [&'static str] {
+        static TYPES: Lazy<&[&str]> = Lazy::new(|| unknown::variants(BlockData::deserialize));
+        &TYPES
+    }
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+
+    fn unknown(r#type: String, raw: serde_json::Value) -> Self {
+        Self::Unknown { r#type, raw }
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        unknown::deserialize(deserializer)
+    }
+}
+
+impl Serialize for BlockData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown { r#type, raw } => unknown::serialize(r#type, raw, serializer),
+            known => Self::serialize(known, serializer),
+        }
+    }
 }
 
 impl BlockData {
+    /// A paragraph of unformatted text.
+    pub fn paragraph(text: impl Into<String>) -> Self {
+        Self::Paragraph {
+            rich_text: vec![RichText::plain(text)],
+            color: Color::Default,
+            children: Vec::new(),
+        }
+    }
+
+    /// The nested children to create along with this block.
+    #[must_use]
+    pub fn children(&self) -> &[Self] {
+        match self {
+            Self::BulletedListItem { children, .. }
+            | Self::Callout { children, .. }
+            | Self::Column { children }
+            | Self::ColumnList { children }
+            | Self::NumberedListItem { children, .. }
+            | Self::Paragraph { children, .. }
+            | Self::Quote { children, .. }
+            | Self::SyncedBlock { children, .. }
+            | Self::Table { children, .. }
+            | Self::Template { children, .. }
+            | Self::ToDo { children, .. }
+            | Self::Toggle { children, .. } => children,
+            Self::Heading1(heading) | Self::Heading2(heading) | Self::Heading3(heading) => {
+                &heading.children
+            }
+            _ => &[],
+        }
+    }
+
+    /// The nested children to create along with this block, or `None` if the block cannot have
+    /// children.
+    pub fn children_mut(&mut self) -> Option<&mut Vec<Self>> {
+        match self {
+            Self::BulletedListItem { children, .. }
+            | Self::Callout { children, .. }
+            | Self::Column { children }
+            | Self::ColumnList { children }
+            | Self::NumberedListItem { children, .. }
+            | Self::Paragraph { children, .. }
+            | Self::Quote { children, .. }
+            | Self::SyncedBlock { children, .. }
+            | Self::Table { children, .. }
+            | Self::Template { children, .. }
+            | Self::ToDo { children, .. }
+            | Self::Toggle { children, .. } => Some(children),
+            Self::Heading1(heading) | Self::Heading2(heading) | Self::Heading3(heading) => {
+                Some(&mut heading.children)
+            }
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub const fn heading(&self) -> Option<&Heading> {
         match self {
@@ -145,3 +421,96 @@ impl BlockData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Block, BlockData, LinkToPage, SyncedFrom};
+    use crate::data::{FileObject, Icon};
+
+    const BLOCKS: &str = include_str!("../../tests/fixtures/blocks.json");
+
+    fn fixtures() -> Vec<serde_json::Value> {
+        let list: serde_json::Value = serde_json::from_str(BLOCKS).unwrap();
+        list["results"].as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn block_payloads_round_trip() {
+        for raw in fixtures() {
+            let ty = raw["type"].as_str().unwrap();
+            let block: Block = serde_json::from_value(raw.clone())
+                .unwrap_or_else(|err| panic!("failed to decode {ty}: {err}"));
+            assert_eq!(block.r#type, ty);
+
+            let payload = serde_json::to_value(&block.data).unwrap();
+            assert_eq!(payload, serde_json::json!({ ty: raw[ty] }), "{ty}");
+        }
+    }
+
+    #[test]
+    fn typed_block_payloads() {
+        let blocks: Vec<Block> = fixtures()
+            .into_iter()
+            .map(|raw| serde_json::from_value(raw).unwrap())
+            .collect();
+        let find = |ty: &'static str| blocks.iter().filter(move |block| block.r#type == ty);
+
+        let Some(BlockData::ToDo { checked, .. }) = find("to_do").next().map(|b| &b.data) else {
+            panic!("expected to_do");
+        };
+        assert!(checked);
+
+        let Some(BlockData::Code { language, .. }) = find("code").next().map(|b| &b.data) else {
+            panic!("expected code");
+        };
+        assert_eq!(language, "rust");
+
+        let Some(BlockData::Table {
+            table_width,
+            has_column_header,
+            ..
+        }) = find("table").next().map(|b| &b.data)
+        else {
+            panic!("expected table");
+        };
+        assert_eq!((*table_width, *has_column_header), (2, true));
+
+        let synced: Vec<_> = find("synced_block")
+            .map(|block| match &block.data {
+                BlockData::SyncedBlock { synced_from, .. } => synced_from.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(synced[0], None);
+        assert!(matches!(synced[1], Some(SyncedFrom::BlockId { .. })));
+
+        let Some(BlockData::File(file)) = find("file").next().map(|b| &b.data) else {
+            panic!("expected file");
+        };
+        assert_eq!(file.name.as_deref(), Some("report.pdf"));
+        assert!(matches!(file.file, FileObject::File { .. }));
+
+        let Some(BlockData::Callout { icon, .. }) = find("callout").next().map(|b| &b.data) else {
+            panic!("expected callout");
+        };
+        assert_eq!(icon, &Some(Icon::emoji("⭐")));
+
+        let Some(BlockData::LinkToPage(link)) = find("link_to_page").next().map(|b| &b.data) else {
+            panic!("expected link_to_page");
+        };
+        assert_eq!(
+            link,
+            &LinkToPage::PageId {
+                page_id: "59833787-2cf9-4fdf-8782-e53db20768a5".parse().unwrap()
+            }
+        );
+
+        let Some(BlockData::Image(image)) = find("image").next().map(|b| &b.data) else {
+            panic!("expected image");
+        };
+        assert_eq!(
+            image.file,
+            FileObject::external("https://website.domain/images/image.png")
+        );
+    }
+}
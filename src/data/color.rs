@@ -1,10 +1,16 @@
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy)]
-#[serde(rename_all = "snake_case")]
+use once_cell::sync::Lazy;
+use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::data::unknown;
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case", remote = "Self")]
 pub enum Color {
     Blue,
     BlueBackground,
     Brown,
     BrownBackground,
+    #[default]
     Default,
     Gray,
     GrayBackground,
@@ -20,4 +26,29 @@ pub enum Color {
     Red,
     RedBackground,
     YellowBackground,
+    /// A color this crate does not know about yet. Serializes back to the same name.
+    #[serde(skip)]
+    Unknown(String),
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        static KNOWN: Lazy<&[&str]> = Lazy::new(|| unknown::variants(Color::deserialize));
+
+        let name = String::deserialize(deserializer)?;
+        if KNOWN.contains(&name.as_str()) {
+            Self::deserialize(name.into_deserializer())
+        } else {
+            Ok(Self::Unknown(name))
+        }
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown(name) => serializer.serialize_str(name),
+            known => Self::serialize(known, serializer),
+        }
+    }
 }
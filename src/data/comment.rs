@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::{parent_object::Parent, rich_text::RichText, user::PartialUser, DateTime};
+
+/// A comment on a page or block.
+///
+/// Comments on the same thread share a `discussion_id`. Reply to a thread with
+/// [`CreateComment::reply`](crate::query::CreateComment::reply).
+///
+/// [Reference](https://developers.notion.com/reference/comment-object)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Comment {
+    pub id: String,
+    /// The page for page-level comments, otherwise the block that was commented on.
+    pub parent: Parent,
+    pub discussion_id: String,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub created_time: DateTime,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub last_edited_time: DateTime,
+    pub created_by: PartialUser,
+    pub rich_text: Vec<RichText>,
+}
+
+impl Comment {
+    /// The text as plain text.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        self.rich_text
+            .iter()
+            .map(|text| text.plain_text.as_str())
+            .collect()
+    }
+}
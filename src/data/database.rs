@@ -0,0 +1,537 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::skip_serializing_none;
+
+use crate::{
+    data::{
+        color::Color,
+        file::{FileObject, Icon},
+        parent_object::Parent,
+        rich_text::RichText,
+        unknown::{self, Tagged},
+        DateTime, PartialUser,
+    },
+    DatabaseId,
+};
+
+/// [Reference](https://developers.notion.com/reference/database)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Database {
+    pub id: DatabaseId,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub created_time: DateTime,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub last_edited_time: DateTime,
+    pub created_by: PartialUser,
+    pub last_edited_by: PartialUser,
+    pub title: Vec<RichText>,
+    #[serde(default)]
+    pub description: Vec<RichText>,
+    pub icon: Option<Icon>,
+    pub cover: Option<FileObject>,
+    /// Map: property name -> schema
+    pub properties: HashMap<String, PropertySchema>,
+    pub parent: Option<Parent>,
+    pub url: String,
+    pub archived: bool,
+    #[serde(default)]
+    pub is_inline: bool,
+}
+
+impl Database {
+    /// The title as plain text.
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.title
+            .iter()
+            .map(|text| text.plain_text.as_str())
+            .collect()
+    }
+}
+
+/// [Reference](https://developers.notion.com/reference/property-object)
+#[derive(Serialize, Debug, Clone)]
+pub struct PropertySchema {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub config: PropertyConfig,
+}
+
+impl<'de> Deserialize<'de> for PropertySchema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // See `Block`: `config` cannot be flattened.
+        #[derive(Deserialize)]
+        struct Raw {
+            id: String,
+            name: String,
+            r#type: String,
+            #[serde(flatten)]
+            rest: serde_json::Map<String, serde_json::Value>,
+        }
+
+        let mut raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            config: unknown::from_payload(&raw.r#type, &mut raw.rest)?,
+            id: raw.id,
+            name: raw.name,
+        })
+    }
+}
+
+/// The type of a database property and its type-specific configuration.
+///
+/// Also used to add properties in [`CreateDatabase`](crate::query::CreateDatabase) and
+/// [`UpdateDatabase`](crate::query::UpdateDatabase).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", remote = "Self")]
+pub enum PropertyConfig {
+    /// Cannot be created or updated through the API.
+    Button {},
+    Checkbox {},
+    CreatedBy {},
+    CreatedTime {},
+    Date {},
+    Email {},
+    Files {},
+    Formula(FormulaConfig),
+    LastEditedBy {},
+    LastEditedTime {},
+    /// Cannot be created or updated through the API.
+    Location {},
+    MultiSelect(SelectConfig),
+    Number(NumberConfig),
+    People {},
+    PhoneNumber {},
+    /// Cannot be created or updated through the API.
+    Place {},
+    Relation(RelationConfig),
+    RichText {},
+    Rollup(RollupConfig),
+    Select(SelectConfig),
+    /// Cannot be created or updated through the API.
+    Status(StatusConfig),
+    Title {},
+    UniqueId(UniqueIdConfig),
+    Url {},
+    /// Cannot be created or updated through the API.
+    Verification {},
+    /// A property type this crate does not know about yet. Serializes back to the same payload.
+    #[serde(skip)]
+    Unknown {
+        r#type: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl Tagged for PropertyConfig {
+    const NAME: &'static str = "property schema";
+
+    fn known_types() -> &'static [&'static str] {
+        static TYPES: Lazy<&[&str]> = Lazy::new(|| unknown::variants(PropertyConfig::deserialize));
+        &TYPES
+    }
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+
+    fn unknown(r#type: String, raw: serde_json::Value) -> Self {
+        Self::Unknown { r#type, raw }
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        unknown::deserialize(deserializer)
+    }
+}
+
+impl Serialize for PropertyConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown { r#type, raw } => unknown::serialize(r#type, raw, serializer),
+            known => Self::serialize(known, serializer),
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UniqueIdConfig {
+    /// Shown before the number, e.g. `TASK` for `TASK-12`.
+    pub prefix: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FormulaConfig {
+    pub expression: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct SelectConfig {
+    pub options: Vec<SelectOption>,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SelectOption {
+    /// Assigned by Notion.
+    pub id: Option<String>,
+    /// Must not contain commas.
+    pub name: String,
+    pub color: Option<Color>,
+}
+
+impl SelectOption {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            color: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusConfig {
+    pub options: Vec<SelectOption>,
+    pub groups: Vec<StatusGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusGroup {
+    pub id: String,
+    pub name: String,
+    pub color: Color,
+    pub option_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct NumberConfig {
+    pub format: NumberFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case", remote = "Self")]
+pub enum NumberFormat {
+    #[default]
+    Number,
+    NumberWithCommas,
+    Percent,
+    Dollar,
+    CanadianDollar,
+    SingaporeDollar,
+    Euro,
+    Pound,
+    Yen,
+    Ruble,
+    Rupee,
+    Won,
+    Yuan,
+    Real,
+    Lira,
+    Rupiah,
+    Franc,
+    HongKongDollar,
+    NewZealandDollar,
+    Krona,
+    NorwegianKrone,
+    MexicanPeso,
+    Rand,
+    NewTaiwanDollar,
+    DanishKrone,
+    Zloty,
+    Baht,
+    Forint,
+    Koruna,
+    Shekel,
+    ChileanPeso,
+    PhilippinePeso,
+    Dirham,
+    ColombianPeso,
+    Riyal,
+    Ringgit,
+    Leu,
+    ArgentinePeso,
+    UruguayanPeso,
+    /// A format this crate does not know about yet. Serializes back to the same name.
+    #[serde(skip)]
+    Unknown(String),
+}
+
+impl<'de> Deserialize<'de> for NumberFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        static KNOWN: Lazy<&[&str]> = Lazy::new(|| unknown::variants(NumberFormat::deserialize));
+
+        let name = String::deserialize(deserializer)?;
+        if KNOWN.contains(&name.as_str()) {
+            Self::deserialize(name.into_deserializer())
+        } else {
+            Ok(Self::Unknown(name))
+        }
+    }
+}
+
+impl Serialize for NumberFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown(name) => serializer.serialize_str(name),
+            known => Self::serialize(known, serializer),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RelationConfig {
+    pub database_id: DatabaseId,
+    #[serde(flatten)]
+    pub kind: RelationKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    /// The relation only shows up in this database.
+    SingleProperty {},
+    /// The relation also shows up in the related database.
+    DualProperty {
+        /// Assigned by Notion.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        synced_property_name: Option<String>,
+        /// Assigned by Notion.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        synced_property_id: Option<String>,
+    },
+}
+
+/// Either the name or the id of each property has to be set when creating a rollup.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RollupConfig {
+    pub relation_property_name: Option<String>,
+    pub relation_property_id: Option<String>,
+    pub rollup_property_name: Option<String>,
+    pub rollup_property_id: Option<String>,
+    pub function: RollupFunction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", remote = "Self")]
+pub enum RollupFunction {
+    Average,
+    Checked,
+    Count,
+    CountPerGroup,
+    CountValues,
+    DateRange,
+    EarliestDate,
+    Empty,
+    LatestDate,
+    Max,
+    Median,
+    Min,
+    NotEmpty,
+    PercentChecked,
+    PercentEmpty,
+    PercentNotEmpty,
+    PercentPerGroup,
+    PercentUnchecked,
+    Range,
+    ShowOriginal,
+    ShowUnique,
+    Sum,
+    Unchecked,
+    Unique,
+    /// A function this crate does not know about yet. Serializes back to the same name.
+    #[serde(skip)]
+    Unknown(String),
+}
+
+impl<'de> Deserialize<'de> for RollupFunction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        static KNOWN: Lazy<&[&str]> = Lazy::new(|| unknown::variants(RollupFunction::deserialize));
+
+        let name = String::deserialize(deserializer)?;
+        if KNOWN.contains(&name.as_str()) {
+            Self::deserialize(name.into_deserializer())
+        } else {
+            Ok(Self::Unknown(name))
+        }
+    }
+}
+
+impl Serialize for RollupFunction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown(name) => serializer.serialize_str(name),
+            known => Self::serialize(known, serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Database, NumberConfig, NumberFormat, PropertyConfig, RelationKind, RollupFunction,
+        UniqueIdConfig,
+    };
+
+    #[test]
+    fn deser_database() {
+        let input = r#"{
+            "object": "database",
+            "id": "bc1211ca-e3f1-4939-ae34-5260b16f627c",
+            "created_time": "2021-07-08T23:50:00.000Z",
+            "last_edited_time": "2021-07-08T23:50:00.000Z",
+            "created_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "last_edited_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "icon": { "type": "emoji", "emoji": "🎉" },
+            "cover": null,
+            "url": "https://www.notion.so/bc1211cae3f14939ae34260b16f627c",
+            "title": [
+                {
+                    "type": "text",
+                    "text": { "content": "Grocery List", "link": null },
+                    "annotations": {
+                        "bold": false, "italic": false, "strikethrough": false,
+                        "underline": false, "code": false, "color": "default"
+                    },
+                    "plain_text": "Grocery List",
+                    "href": null
+                }
+            ],
+            "description": [],
+            "properties": {
+                "Price": {
+                    "id": "evWq",
+                    "name": "Price",
+                    "type": "number",
+                    "number": { "format": "dollar" }
+                },
+                "Store availability": {
+                    "id": "flsb",
+                    "name": "Store availability",
+                    "type": "multi_select",
+                    "multi_select": {
+                        "options": [
+                            { "id": "5de29601-9c24-4b04-8629-0bca891c5120", "name": "Duc Loi Market", "color": "blue" }
+                        ]
+                    }
+                },
+                "Recipes": {
+                    "id": "YfIu",
+                    "name": "Recipes",
+                    "type": "relation",
+                    "relation": {
+                        "database_id": "668d797c-76fa-4934-9b05-ad288df2d136",
+                        "type": "dual_property",
+                        "dual_property": {
+                            "synced_property_name": "Ingredients",
+                            "synced_property_id": "wxA]"
+                        }
+                    }
+                },
+                "Cost of next trip": {
+                    "id": "WOd%3B",
+                    "name": "Cost of next trip",
+                    "type": "formula",
+                    "formula": { "expression": "if(prop(\"In stock\"), 0, prop(\"Price\"))" }
+                },
+                "Number of meals": {
+                    "id": "Z\\Eh",
+                    "name": "Number of meals",
+                    "type": "rollup",
+                    "rollup": {
+                        "rollup_property_name": "Name",
+                        "relation_property_name": "Meals",
+                        "rollup_property_id": "title",
+                        "relation_property_id": "mxp^",
+                        "function": "count"
+                    }
+                },
+                "Name": { "id": "title", "name": "Name", "type": "title", "title": {} }
+            },
+            "parent": { "type": "page_id", "page_id": "98ad959b-2b6a-4774-80ee-00246fb0ea9b" },
+            "archived": false,
+            "is_inline": false
+        }"#;
+
+        let database: Database = serde_json::from_str(input).unwrap();
+        assert_eq!(database.title(), "Grocery List");
+
+        let price = &database.properties["Price"].config;
+        assert_eq!(
+            price,
+            &PropertyConfig::Number(super::NumberConfig {
+                format: NumberFormat::Dollar
+            })
+        );
+
+        let PropertyConfig::Relation(relation) = &database.properties["Recipes"].config else {
+            panic!("expected relation");
+        };
+        assert!(matches!(relation.kind, RelationKind::DualProperty { .. }));
+
+        assert_eq!(database.properties["Name"].config, PropertyConfig::Title {});
+    }
+
+    #[test]
+    fn deser_newer_property_types() {
+        let raw: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/database.json")).unwrap();
+        let database: Database = serde_json::from_value(raw.clone()).unwrap();
+        let config = |name: &str| &database.properties[name].config;
+
+        assert_eq!(
+            config("ID"),
+            &PropertyConfig::UniqueId(UniqueIdConfig {
+                prefix: Some("TASK".to_string())
+            })
+        );
+        assert_eq!(config("Verified"), &PropertyConfig::Verification {});
+        assert_eq!(config("Start"), &PropertyConfig::Button {});
+        assert_eq!(config("Office"), &PropertyConfig::Place {});
+        assert_eq!(config("Where"), &PropertyConfig::Location {});
+        assert_eq!(
+            config("Budget"),
+            &PropertyConfig::Number(NumberConfig {
+                format: NumberFormat::Unknown("bitcoin".to_string())
+            })
+        );
+        let PropertyConfig::Rollup(rollup) = config("Spread") else {
+            panic!("expected rollup");
+        };
+        assert_eq!(
+            rollup.function,
+            RollupFunction::Unknown("standard_deviation".to_string())
+        );
+        assert!(matches!(
+            config("Hologram"),
+            PropertyConfig::Unknown { r#type, .. } if r#type == "hologram"
+        ));
+
+        // Unknown types and names serialize back to the same payload.
+        let serialized = serde_json::to_value(&database.properties["Hologram"]).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({ "id": "hlgr", "name": "Hologram", "hologram": { "depth": 3 } })
+        );
+        let serialized = serde_json::to_value(config("Spread")).unwrap();
+        assert_eq!(serialized["rollup"], raw["properties"]["Spread"]["rollup"]);
+
+        // The same database decodes as an object, as in search results.
+        let object: crate::data::Object = serde_json::from_value(raw).unwrap();
+        assert!(matches!(object, crate::data::Object::Database(_)));
+    }
+}
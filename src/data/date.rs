@@ -0,0 +1,271 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A point in time, e.g. `created_time`.
+pub type DateTime = chrono::DateTime<Utc>;
+
+/// A date, date time or range of either.
+///
+/// [Reference](https://developers.notion.com/reference/property-value-object#date-property-values)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub start: DateValue,
+    pub end: Option<DateValue>,
+    /// The time zone `start` and `end` are in. Times have the offset of this zone when decoded
+    /// and are sent as local times in it.
+    pub time_zone: Option<Tz>,
+}
+
+impl Date {
+    /// A single date or date time.
+    pub fn new(start: impl Into<DateValue>) -> Self {
+        Self {
+            start: start.into(),
+            end: None,
+            time_zone: None,
+        }
+    }
+
+    /// A range from `start` to `end`.
+    pub fn range(start: impl Into<DateValue>, end: impl Into<DateValue>) -> Self {
+        Self {
+            end: Some(end.into()),
+            ..Self::new(start)
+        }
+    }
+
+    #[must_use]
+    pub const fn time_zone(mut self, time_zone: Tz) -> Self {
+        self.time_zone = Some(time_zone);
+        self
+    }
+}
+
+/// A whole day or a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateValue {
+    /// `2023-03-08`
+    Date(NaiveDate),
+    /// `2023-03-08T18:25:00.000+01:00`
+    DateTime(chrono::DateTime<FixedOffset>),
+}
+
+impl DateValue {
+    /// The day, in the offset of the date time.
+    #[must_use]
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Self::Date(date) => *date,
+            Self::DateTime(date_time) => date_time.date_naive(),
+        }
+    }
+
+    #[must_use]
+    pub const fn has_time(&self) -> bool {
+        matches!(self, Self::DateTime(_))
+    }
+
+    /// Times without an offset are in `time_zone`, or UTC without one.
+    fn parse(input: &str, time_zone: Option<Tz>) -> Result<Self, chrono::ParseError> {
+        if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+            return Ok(Self::Date(date));
+        }
+
+        let date_time = match chrono::DateTime::parse_from_rfc3339(input) {
+            Ok(date_time) => date_time,
+            Err(err) => {
+                let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map_err(|_| err)?;
+                match time_zone {
+                    Some(tz) => tz
+                        .from_local_datetime(&naive)
+                        .earliest()
+                        .ok_or(err)?
+                        .fixed_offset(),
+                    None => naive.and_utc().fixed_offset(),
+                }
+            }
+        };
+
+        Ok(Self::DateTime(match time_zone {
+            Some(tz) => date_time.with_timezone(&tz).fixed_offset(),
+            None => date_time,
+        }))
+    }
+
+    /// Notion expects local times without an offset if a time zone is given.
+    fn to_api_string(self, time_zone: Option<Tz>) -> String {
+        match (self, time_zone) {
+            (Self::DateTime(date_time), Some(tz)) => date_time
+                .with_timezone(&tz)
+                .naive_local()
+                .format("%Y-%m-%dT%H:%M:%S%.3f")
+                .to_string(),
+            (value, _) => value.to_string(),
+        }
+    }
+}
+
+impl From<NaiveDate> for DateValue {
+    fn from(date: NaiveDate) -> Self {
+        Self::Date(date)
+    }
+}
+
+impl<Tz: TimeZone> From<chrono::DateTime<Tz>> for DateValue {
+    fn from(date_time: chrono::DateTime<Tz>) -> Self {
+        Self::DateTime(date_time.fixed_offset())
+    }
+}
+
+/// Parses `2023-03-08` and RFC 3339 date times. Date times without an offset are in UTC.
+impl FromStr for DateValue {
+    type Err = chrono::ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input, None)
+    }
+}
+
+impl fmt::Display for DateValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            Self::DateTime(date_time) => {
+                f.write_str(&date_time.to_rfc3339_opts(SecondsFormat::Millis, true))
+            }
+        }
+    }
+}
+
+/// As ISO 8601, in the format of [`Display`](fmt::Display).
+impl Serialize for DateValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawDate {
+    start: String,
+    end: Option<String>,
+    time_zone: Option<Tz>,
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawDate {
+            start: self.start.to_api_string(self.time_zone),
+            end: self.end.map(|end| end.to_api_string(self.time_zone)),
+            time_zone: self.time_zone,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawDate::deserialize(deserializer)?;
+        let parse = |input: &str| DateValue::parse(input, raw.time_zone).map_err(de::Error::custom);
+        Ok(Self {
+            start: parse(&raw.start)?,
+            end: raw.end.as_deref().map(parse).transpose()?,
+            time_zone: raw.time_zone,
+        })
+    }
+}
+
+/// (De)serializes a [`DateTime`] with milliseconds, as Notion does, e.g.
+/// `2023-03-08T18:25:00.000Z`.
+pub(crate) mod timestamp {
+    use chrono::SecondsFormat;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::DateTime;
+
+    pub fn serialize<S: Serializer>(time: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Millis, true))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        DateTime::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+    use chrono_tz::Tz;
+    use serde_json::json;
+
+    use super::{Date, DateValue};
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn dates_and_date_times() {
+        let date: Date = serde_json::from_value(
+            json!({ "start": "2023-03-08", "end": null, "time_zone": null }),
+        )
+        .unwrap();
+        assert_eq!(date, Date::new(day(2023, 3, 8)));
+        assert!(!date.start.has_time());
+
+        let date: Date = serde_json::from_value(json!({
+            "start": "2023-03-08T18:25:00.000+01:00",
+            "end": "2023-03-09T01:00:00.000Z",
+            "time_zone": null
+        }))
+        .unwrap();
+        assert_eq!(date.start.to_string(), "2023-03-08T18:25:00.000+01:00");
+        assert_eq!(date.end.unwrap().date(), day(2023, 3, 9));
+        assert_eq!(
+            serde_json::to_value(date).unwrap()["end"],
+            "2023-03-09T01:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn time_zone_is_applied() {
+        let date: Date = serde_json::from_value(json!({
+            "start": "2023-03-08T17:25:00.000Z",
+            "end": null,
+            "time_zone": "Europe/Berlin"
+        }))
+        .unwrap();
+        let DateValue::DateTime(start) = date.start else {
+            panic!("expected date time");
+        };
+        assert_eq!(start.to_rfc3339(), "2023-03-08T18:25:00+01:00");
+        assert_eq!(
+            serde_json::to_value(date).unwrap(),
+            json!({ "start": "2023-03-08T18:25:00.000", "end": null, "time_zone": "Europe/Berlin" })
+        );
+
+        // Notion accepts local times for a time zone, so they are decoded the same way.
+        let local: Date = serde_json::from_value(json!({
+            "start": "2023-03-08T18:25:00.000",
+            "end": null,
+            "time_zone": "Europe/Berlin"
+        }))
+        .unwrap();
+        assert_eq!(local, date);
+
+        let berlin = Tz::Europe__Berlin
+            .with_ymd_and_hms(2023, 3, 8, 18, 25, 0)
+            .unwrap();
+        assert_eq!(Date::new(berlin).time_zone(Tz::Europe__Berlin), date);
+    }
+
+    #[test]
+    fn invalid_dates_fail() {
+        let date = json!({ "start": "next tuesday", "end": null, "time_zone": null });
+        assert!(serde_json::from_value::<Date>(date).is_err());
+        assert!("2023-02-30".parse::<DateValue>().is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::{property::ExternalUrl, DateTime};
+
+/// A file that is either hosted by Notion or linked from elsewhere.
+///
+/// [Reference](https://developers.notion.com/reference/file-object)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FileObject {
+    External { external: ExternalUrl },
+    File { file: HostedFile },
+}
+
+impl FileObject {
+    /// A file linked from elsewhere. The only kind that can be created through the API.
+    pub fn external(url: impl Into<String>) -> Self {
+        Self::External {
+            external: ExternalUrl { url: url.into() },
+        }
+    }
+
+    /// Where the file can be downloaded from. Expires for files hosted by Notion.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        match self {
+            Self::External { external } => &external.url,
+            Self::File { file } => &file.url,
+        }
+    }
+}
+
+/// A file uploaded to Notion.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HostedFile {
+    /// An authenticated URL that is valid until `expiry_time`.
+    pub url: String,
+    #[serde(with = "crate::data::date::timestamp")]
+    pub expiry_time: DateTime,
+}
+
+/// A file with a name, as used by the files property.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NamedFile {
+    pub name: String,
+    #[serde(flatten)]
+    pub file: FileObject,
+}
+
+impl NamedFile {
+    /// A file linked from elsewhere. The only kind that can be created through the API.
+    pub fn external(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            file: FileObject::external(url),
+        }
+    }
+}
+
+/// The icon of a page, database or callout block.
+///
+/// Only emojis and external files can be set through the API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Icon {
+    Emoji { emoji: String },
+    External { external: ExternalUrl },
+    File { file: HostedFile },
+    CustomEmoji { custom_emoji: CustomEmoji },
+}
+
+impl Icon {
+    pub fn emoji(emoji: impl Into<String>) -> Self {
+        Self::Emoji {
+            emoji: emoji.into(),
+        }
+    }
+
+    pub fn external(url: impl Into<String>) -> Self {
+        Self::External {
+            external: ExternalUrl { url: url.into() },
+        }
+    }
+}
+
+/// An emoji uploaded to the workspace.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CustomEmoji {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{FileObject, Icon, NamedFile};
+
+    #[test]
+    fn deser_file_objects() {
+        let hosted: FileObject = serde_json::from_value(json!({
+            "type": "file",
+            "file": {
+                "url": "https://s3.us-west-2.amazonaws.com/secure.notion-static.com/cover.png",
+                "expiry_time": "2023-03-13T04:32:00.000Z"
+            }
+        }))
+        .unwrap();
+        let FileObject::File { file } = &hosted else {
+            panic!("expected hosted file");
+        };
+        assert_eq!(file.expiry_time.to_rfc3339(), "2023-03-13T04:32:00+00:00");
+        assert_eq!(hosted.url(), file.url);
+
+        let named: NamedFile = serde_json::from_value(json!({
+            "name": "menu.pdf",
+            "type": "external",
+            "external": { "url": "https://example.com/menu.pdf" }
+        }))
+        .unwrap();
+        assert_eq!(
+            named,
+            NamedFile::external("menu.pdf", "https://example.com/menu.pdf")
+        );
+    }
+
+    #[test]
+    fn icons() {
+        let icon: Icon = serde_json::from_value(json!({
+            "type": "custom_emoji",
+            "custom_emoji": {
+                "id": "45ce454c-d427-4f53-9489-e5d0f3d1db6b",
+                "name": "bufo",
+                "url": "https://s3-us-west-2.amazonaws.com/public.notion-static.com/bufo.png"
+            }
+        }))
+        .unwrap();
+        assert!(matches!(icon, Icon::CustomEmoji { custom_emoji } if custom_emoji.name == "bufo"));
+
+        assert_eq!(
+            serde_json::to_value(Icon::emoji("🥬")).unwrap(),
+            json!({ "type": "emoji", "emoji": "🥬" })
+        );
+    }
+}
@@ -1,15 +1,88 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
-pub enum Data {
-    PageId(String),
-    DatabaseId(String),
+use crate::{BlockId, DatabaseId, PageId};
+
+/// Where a page, database or block lives.
+///
+/// [Reference](https://developers.notion.com/reference/parent-object)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Parent {
+    PageId {
+        page_id: PageId,
+    },
+    DatabaseId {
+        database_id: DatabaseId,
+    },
+    /// A block on a page, e.g. a toggle or column that contains a child page.
+    BlockId {
+        block_id: BlockId,
+    },
+    /// The top level of the workspace. Always `true`.
+    Workspace {
+        workspace: bool,
+    },
+}
+
+impl Parent {
+    /// A page as the parent.
+    pub fn page(page_id: impl Into<PageId>) -> Self {
+        Self::PageId {
+            page_id: page_id.into(),
+        }
+    }
+
+    /// A database as the parent.
+    pub fn database(database_id: impl Into<DatabaseId>) -> Self {
+        Self::DatabaseId {
+            database_id: database_id.into(),
+        }
+    }
+
+    /// A block as the parent.
+    pub fn block(block_id: impl Into<BlockId>) -> Self {
+        Self::BlockId {
+            block_id: block_id.into(),
+        }
+    }
+
+    /// The top level of the workspace.
+    #[must_use]
+    pub const fn workspace() -> Self {
+        Self::Workspace { workspace: true }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ParentObject {
-    pub r#type: String,
-    #[serde(flatten)]
-    pub data: Data,
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Parent;
+
+    #[test]
+    fn deser_every_parent() {
+        let id = "59833787-2cf9-4fdf-8782-e53db20768a5";
+        for (raw, expected) in [
+            (
+                json!({ "type": "page_id", "page_id": id }),
+                Parent::page(id.parse::<crate::PageId>().unwrap()),
+            ),
+            (
+                json!({ "type": "database_id", "database_id": id }),
+                Parent::database(id.parse::<crate::DatabaseId>().unwrap()),
+            ),
+            (
+                json!({ "type": "block_id", "block_id": id }),
+                Parent::block(id.parse::<crate::BlockId>().unwrap()),
+            ),
+            (
+                json!({ "type": "workspace", "workspace": true }),
+                Parent::workspace(),
+            ),
+        ] {
+            let parent: Parent = serde_json::from_value(raw.clone()).unwrap();
+            assert_eq!(parent, expected);
+            assert_eq!(serde_json::to_value(&parent).unwrap(), raw);
+        }
+    }
 }
@@ -1,71 +1,546 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    database::{RollupFunction, SelectOption},
+    date::{Date, DateValue},
+    unknown::{self, Tagged},
+};
+use crate::{PageId, UserId};
+
+/// The value of a page property.
+///
+/// Types that can be empty are `Option`s or empty `Vec`s.
+///
+/// [Reference](https://developers.notion.com/reference/page-property-values)
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case", remote = "Self")]
 pub enum PropertyData {
+    Button {},
     Checkbox(bool),
-    CreatedBy(super::User),
-    CreatedTime(super::DateTime),
-    Date(Date),
-    Email(String),
-    Files(Vec<File>),
+    CreatedBy(super::PartialUser),
+    CreatedTime(#[serde(with = "super::date::timestamp")] super::DateTime),
+    Date(Option<Date>),
+    Email(Option<String>),
+    Files(Vec<super::file::NamedFile>),
     Formula(Formula),
-    LastEditedBy(super::User),
-    LastEditedTime(super::DateTime),
-    MultiSelect(serde_json::Value),
-    Number(i64),
-    People(Vec<super::User>),
-    PhoneNumber(String),
-    Relation(serde_json::Value),
-    Rollup(serde_json::Value),
-    RichText(RichText),
-    Select(serde_json::Value),
-    Status(Status),
-    Title(Vec<super::TextBlock>),
-    Url(String),
+    LastEditedBy(super::PartialUser),
+    LastEditedTime(#[serde(with = "super::date::timestamp")] super::DateTime),
+    MultiSelect(Vec<SelectOption>),
+    Number(Option<f64>),
+    People(Vec<super::MaybePartialUser>),
+    PhoneNumber(Option<String>),
+    /// At most 25 pages. See [`Property::has_more`].
+    Relation(Vec<Reference<PageId>>),
+    Rollup(Rollup),
+    RichText(Vec<super::rich_text::RichText>),
+    Select(Option<SelectOption>),
+    Status(Option<SelectOption>),
+    Title(Vec<super::rich_text::RichText>),
+    UniqueId(UniqueId),
+    Url(Option<String>),
+    Verification(Verification),
+    /// A property type this crate does not know about yet. Serializes back to the same payload.
+    #[serde(skip)]
+    Unknown {
+        r#type: String,
+        raw: serde_json::Value,
+    },
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-pub struct RichText(serde_json::Value);
+impl Tagged for PropertyData {
+    const NAME: &'static str = "property";
 
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+    fn known_types() -> &'static [&'static str] {
+        static TYPES: Lazy<&[&str]> = Lazy::new(|| unknown::variants(PropertyData::deserialize));
+        &TYPES
+    }
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+
+    fn unknown(r#type: String, raw: serde_json::Value) -> Self {
+        Self::Unknown { r#type, raw }
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        unknown::deserialize(deserializer)
+    }
+}
+
+impl Serialize for PropertyData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown { r#type, raw } => unknown::serialize(r#type, raw, serializer),
+            known => Self::serialize(known, serializer),
+        }
+    }
+}
+
+/// Accessors that return `None` if the property has another type or is empty.
+impl PropertyData {
+    #[must_use]
+    pub const fn as_checkbox(&self) -> Option<bool> {
+        match self {
+            Self::Checkbox(checked) => Some(*checked),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_created_by(&self) -> Option<&super::PartialUser> {
+        match self {
+            Self::CreatedBy(user) => Some(user),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_created_time(&self) -> Option<&super::DateTime> {
+        match self {
+            Self::CreatedTime(time) => Some(time),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_date(&self) -> Option<&Date> {
+        match self {
+            Self::Date(date) => date.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_email(&self) -> Option<&str> {
+        match self {
+            Self::Email(email) => email.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_files(&self) -> Option<&[super::file::NamedFile]> {
+        match self {
+            Self::Files(files) => Some(files),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_formula(&self) -> Option<&Formula> {
+        match self {
+            Self::Formula(formula) => Some(formula),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_last_edited_by(&self) -> Option<&super::PartialUser> {
+        match self {
+            Self::LastEditedBy(user) => Some(user),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_last_edited_time(&self) -> Option<&super::DateTime> {
+        match self {
+            Self::LastEditedTime(time) => Some(time),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_multi_select(&self) -> Option<&[SelectOption]> {
+        match self {
+            Self::MultiSelect(options) => Some(options),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => *number,
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_people(&self) -> Option<&[super::MaybePartialUser]> {
+        match self {
+            Self::People(people) => Some(people),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_phone_number(&self) -> Option<&str> {
+        match self {
+            Self::PhoneNumber(number) => number.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_relation(&self) -> Option<&[Reference<PageId>]> {
+        match self {
+            Self::Relation(pages) => Some(pages),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_rollup(&self) -> Option<&Rollup> {
+        match self {
+            Self::Rollup(rollup) => Some(rollup),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_rich_text(&self) -> Option<&[super::rich_text::RichText]> {
+        match self {
+            Self::RichText(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_select(&self) -> Option<&SelectOption> {
+        match self {
+            Self::Select(option) => option.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_status(&self) -> Option<&SelectOption> {
+        match self {
+            Self::Status(option) => option.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_title(&self) -> Option<&[super::rich_text::RichText]> {
+        match self {
+            Self::Title(title) => Some(title),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_unique_id(&self) -> Option<&UniqueId> {
+        match self {
+            Self::UniqueId(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_url(&self) -> Option<&str> {
+        match self {
+            Self::Url(url) => url.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_verification(&self) -> Option<&Verification> {
+        match self {
+            Self::Verification(verification) => Some(verification),
+            _ => None,
+        }
+    }
+}
+
+/// Forwards the [`PropertyData`] accessors to [`Property`].
+macro_rules! accessors {
+    ($($name:ident -> $ty:ty),* $(,)?) => {
+        /// The accessors of [`PropertyData`], so values can be read without going through
+        /// [`Property::data`].
+        impl Property {
+            $(
+                #[must_use]
+                pub fn $name(&self) -> $ty {
+                    self.data.$name()
+                }
+            )*
+        }
+    };
+}
+
+accessors! {
+    as_checkbox -> Option<bool>,
+    as_created_by -> Option<&super::PartialUser>,
+    as_created_time -> Option<&super::DateTime>,
+    as_date -> Option<&Date>,
+    as_email -> Option<&str>,
+    as_files -> Option<&[super::file::NamedFile]>,
+    as_formula -> Option<&Formula>,
+    as_last_edited_by -> Option<&super::PartialUser>,
+    as_last_edited_time -> Option<&super::DateTime>,
+    as_multi_select -> Option<&[SelectOption]>,
+    as_number -> Option<f64>,
+    as_people -> Option<&[super::MaybePartialUser]>,
+    as_phone_number -> Option<&str>,
+    as_relation -> Option<&[Reference<PageId>]>,
+    as_rollup -> Option<&Rollup>,
+    as_rich_text -> Option<&[super::rich_text::RichText]>,
+    as_select -> Option<&SelectOption>,
+    as_status -> Option<&SelectOption>,
+    as_title -> Option<&[super::rich_text::RichText]>,
+    as_unique_id -> Option<&UniqueId>,
+    as_url -> Option<&str>,
+    as_verification -> Option<&Verification>,
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
 pub struct Property {
     pub id: String,
     #[serde(flatten)]
     pub data: PropertyData,
+    /// Whether a relation has more pages than included in [`PropertyData::Relation`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_more: bool,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+impl Property {
+    /// Whether [`Client::get_page`](crate::Client::get_page) may have returned only part of the
+    /// value: relations with [`Property::has_more`], titles, rich text and people with 25 items,
+    /// and rollups, which Notion computes from at most 25 related pages.
+    #[must_use]
+    pub fn may_be_truncated(&self) -> bool {
+        const PAGE_LIMIT: usize = 25;
+
+        match &self.data {
+            PropertyData::Relation(_) => self.has_more,
+            PropertyData::Title(text) | PropertyData::RichText(text) => text.len() >= PAGE_LIMIT,
+            PropertyData::People(people) => people.len() >= PAGE_LIMIT,
+            PropertyData::Rollup(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Property {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // See `Block`: `data` cannot be flattened.
+        #[derive(Deserialize)]
+        struct Raw {
+            id: String,
+            r#type: String,
+            #[serde(default)]
+            has_more: bool,
+            #[serde(flatten)]
+            rest: serde_json::Map<String, serde_json::Value>,
+        }
+
+        let mut raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            data: unknown::from_payload(&raw.r#type, &mut raw.rest)?,
+            id: raw.id,
+            has_more: raw.has_more,
+        })
+    }
+}
+
+/// The result of a formula. `None` if the formula has no result, e.g. because a referenced
+/// property is empty.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Formula {
-    Boolean(bool),
-    Date(super::Date),
-    // TODO: is this the right date type
-    Number(i64),
-    String(String),
+    Boolean { boolean: Option<bool> },
+    Date { date: Option<Date> },
+    Number { number: Option<f64> },
+    String { string: Option<String> },
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-pub struct File {
-    pub name: String,
-    pub r#type: String,
-    pub external_url: serde_json::Value,
+/// The result of a rollup.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct Rollup {
+    #[serde(flatten)]
+    pub value: RollupValue,
+    pub function: RollupFunction,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-pub struct Date {
-    pub start: Option<String>,
-    pub end: Option<String>,
-    pub time_zone: Option<String>,
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RollupValue {
+    Number {
+        number: Option<f64>,
+    },
+    Date {
+        date: Option<Date>,
+    },
+    /// The values of the rolled up property, e.g. for
+    /// [`RollupFunction::ShowOriginal`](super::RollupFunction::ShowOriginal).
+    Array {
+        #[serde(deserialize_with = "values_without_id")]
+        array: Vec<PropertyData>,
+    },
+    /// Notion could not compute the whole rollup.
+    Incomplete {},
+    Unsupported {},
 }
 
+/// Rollup arrays contain property values without an id, e.g.
+/// `{ "type": "number", "number": 2 }`.
+fn values_without_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<PropertyData>, D::Error> {
+    #[derive(Deserialize)]
+    struct Raw {
+        r#type: String,
+        #[serde(flatten)]
+        rest: serde_json::Map<String, serde_json::Value>,
+    }
+
+    Vec::<Raw>::deserialize(deserializer)?
+        .into_iter()
+        .map(|mut raw| unknown::from_payload(&raw.r#type, &mut raw.rest))
+        .collect()
+}
+
+/// An automatically incremented id, e.g. `TASK-12`.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct UniqueId {
+    pub number: Option<u64>,
+    pub prefix: Option<String>,
+}
+
+/// The verification state of a page in a wiki.
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
-pub struct Status {
-    pub id: String,
-    pub name: String,
-    pub color: String,
+pub struct Verification {
+    pub state: VerificationState,
+    pub verified_by: Option<super::PartialUser>,
+    /// When the verification started and, if set, expires.
+    pub date: Option<Date>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationState {
+    Verified,
+    Unverified,
+    Expired,
+}
+
+/// A property value that can be written with
+/// [`Client::create_page`](crate::Client::create_page) and
+/// [`Client::update_page`](crate::Client::update_page).
+///
+/// Mirrors [`PropertyData`] without the types that are computed by Notion (formulas, rollups,
+/// created/last edited by/time). `None` clears a value.
+///
+/// [Reference](https://developers.notion.com/reference/page-property-values)
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyValue {
+    Checkbox(bool),
+    Date(Option<Date>),
+    Email(Option<String>),
+    Files(Vec<super::file::NamedFile>),
+    MultiSelect(Vec<SelectValue>),
+    Number(Option<f64>),
+    People(Vec<Reference<UserId>>),
+    PhoneNumber(Option<String>),
+    Relation(Vec<Reference<PageId>>),
+    RichText(Vec<super::rich_text::RichText>),
+    Select(Option<SelectValue>),
+    Status(Option<SelectValue>),
+    Title(Vec<super::rich_text::RichText>),
+    Url(Option<String>),
+}
+
+impl PropertyValue {
+    /// A title without formatting.
+    pub fn title(text: impl Into<String>) -> Self {
+        Self::Title(vec![super::rich_text::RichText::plain(text)])
+    }
+
+    /// Rich text without formatting.
+    pub fn rich_text(text: impl Into<String>) -> Self {
+        Self::RichText(vec![super::rich_text::RichText::plain(text)])
+    }
+
+    /// Select the option with the given name, creating it if it does not exist.
+    pub fn select(name: impl Into<String>) -> Self {
+        Self::Select(Some(SelectValue::name(name)))
+    }
+
+    /// Select the options with the given names, creating them if they do not exist.
+    pub fn multi_select<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Self {
+        Self::MultiSelect(names.into_iter().map(SelectValue::name).collect())
+    }
+
+    /// Relate to the pages with the given ids.
+    pub fn relation(page_ids: impl IntoIterator<Item = PageId>) -> Self {
+        Self::Relation(page_ids.into_iter().map(Reference::new).collect())
+    }
+
+    /// Assign the users with the given ids.
+    pub fn people(user_ids: impl IntoIterator<Item = UserId>) -> Self {
+        Self::People(user_ids.into_iter().map(Reference::new).collect())
+    }
+
+    /// A date or date time without an end. See [`Date`] for ranges and time zones.
+    pub fn date(start: impl Into<DateValue>) -> Self {
+        Self::Date(Some(Date::new(start)))
+    }
+}
+
+/// An option of a select, multi-select or status property, by id or by name.
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, Debug, Clone, Default)]
+pub struct SelectValue {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+impl SelectValue {
+    pub fn id(id: impl Into<String>) -> Self {
+        Self {
+            id: Some(id.into()),
+            name: None,
+        }
+    }
+
+    pub fn name(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: Some(name.into()),
+        }
+    }
+}
+
+/// A page, database or user by id.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Reference<Id> {
+    pub id: Id,
+}
+
+impl<Id> Reference<Id> {
+    pub const fn new(id: Id) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExternalUrl {
+    pub url: String,
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{Formula, Property, PropertyData, RollupValue, VerificationState};
+    use crate::data::{Color, Page, RollupFunction};
+
     #[tokio::test]
     async fn deser_test() {
         let input = r#"
@@ -97,4 +572,89 @@ mod tests {
         let prop = serde_json::from_str::<super::Property>(input).unwrap();
         println!("{prop:#?}");
     }
+
+    #[test]
+    fn deser_every_property_type() {
+        let page: Page =
+            serde_json::from_str(include_str!("../../tests/fixtures/page.json")).unwrap();
+        let prop = |name| page.property(name).unwrap();
+
+        for (name, property) in &page.properties {
+            assert!(
+                !matches!(property.data, PropertyData::Unknown { .. }),
+                "{name} did not decode"
+            );
+        }
+
+        assert_eq!(prop("Price").as_number(), Some(2.5));
+        assert_eq!(prop("Weight").as_number(), None);
+        assert_eq!(prop("In stock").as_checkbox(), Some(true));
+        assert_eq!(prop("Price").as_checkbox(), None);
+        assert_eq!(prop("Status").as_status(), None);
+        assert_eq!(prop("Next order").as_date(), None);
+        assert_eq!(
+            prop("Last ordered").as_date().unwrap().start,
+            "2022-02-22".parse().unwrap()
+        );
+        assert_eq!(prop("Phone").as_phone_number(), None);
+        assert_eq!(prop("Website").as_url(), Some("https://www.example.com"));
+
+        let select = prop("Food group").as_select().unwrap();
+        assert_eq!(select.name, "🥬 Vegetable");
+        assert_eq!(select.color, Some(Color::Pink));
+        assert_eq!(
+            prop("Store availability").as_multi_select().unwrap().len(),
+            1
+        );
+
+        assert_eq!(prop("Recipes").as_relation().unwrap().len(), 1);
+        assert!(page.properties["Recipes"].has_more);
+        assert_eq!(page.properties["Recipes"].as_relation().unwrap().len(), 1);
+        assert_eq!(page.properties["Recipes"].as_number(), None);
+
+        assert_eq!(
+            prop("Cost of next trip").as_formula(),
+            Some(&Formula::Number { number: Some(2.5) })
+        );
+        assert_eq!(
+            prop("Needs restock").as_formula(),
+            Some(&Formula::Boolean { boolean: None })
+        );
+
+        let rollup = prop("Number of meals").as_rollup().unwrap();
+        assert_eq!(rollup.function, RollupFunction::Count);
+        assert!(matches!(rollup.value, RollupValue::Number { number: Some(n) } if n == 2.0));
+
+        let RollupValue::Array { array } = &prop("Meal names").as_rollup().unwrap().value else {
+            panic!("expected rollup array");
+        };
+        assert_eq!(array[0].as_title().unwrap()[0].plain_text, "Kale salad");
+        assert_eq!(array[1].as_number(), Some(3.0));
+
+        let unique_id = prop("Task id").as_unique_id().unwrap();
+        assert_eq!(
+            (unique_id.prefix.as_deref(), unique_id.number),
+            (Some("TASK"), Some(42))
+        );
+
+        let verification = prop("Verified").as_verification().unwrap();
+        assert_eq!(verification.state, VerificationState::Verified);
+
+        assert_eq!(prop("Owner").as_people().unwrap()[0].name(), Some("Ada"));
+        assert_eq!(prop("Photos").as_files().unwrap()[0].name, "kale.png");
+        assert!(matches!(prop("Order"), PropertyData::Button {}));
+    }
+
+    #[test]
+    fn deser_partial_people() {
+        let raw = serde_json::json!({
+            "id": "%3DnNv",
+            "type": "people",
+            "people": [{ "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" }]
+        });
+        let property: Property = serde_json::from_value(raw).unwrap();
+        let people = property.as_people().unwrap();
+        assert_eq!(people[0].id(), "ee5f0f84-409a-440f-983a-a5315961c6e4");
+        assert_eq!(people[0].name(), None);
+    }
 }
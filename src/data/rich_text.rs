@@ -1,29 +1,116 @@
-use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::data::Annotations;
+use crate::{
+    data::{
+        color::Color,
+        date::Date,
+        property::{ExternalUrl, Reference},
+        unknown::{self, Tagged},
+        PartialUser,
+    },
+    markdown, DatabaseId, PageId,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Link {
-    url: String,
+    pub url: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// The styling of rich text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Annotations {
+    pub bold: bool,
+    pub italic: bool,
+    pub strikethrough: bool,
+    pub underline: bool,
+    pub code: bool,
+    pub color: Color,
+}
+
+impl Annotations {
+    /// `text` as Markdown with bold, italic, strikethrough and code applied. Underlines and
+    /// colors have no Markdown equivalent and are dropped.
+    fn to_markdown(&self, text: &str) -> String {
+        // Emphasis must not start or end with whitespace, so it is moved outside.
+        let core = text.trim();
+        if core.is_empty() {
+            return text.to_string();
+        }
+        let lead = &text[..text.len() - text.trim_start().len()];
+        let trail = &text[text.trim_end().len()..];
+
+        let mut res = if self.code {
+            markdown::code_span(core)
+        } else {
+            markdown::escape(core)
+        };
+        if self.strikethrough {
+            res = format!("~~{res}~~");
+        }
+        if self.italic {
+            res = format!("*{res}*");
+        }
+        if self.bold {
+            res = format!("**{res}**");
+        }
+        format!("{lead}{res}{trail}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", remote = "Self")]
 pub enum RichTextData {
     Text {
         content: String,
         link: Option<Link>,
     },
-    /// TODO: finish
-    Mention {
-        r#type: String,
-    },
+    Mention(Mention),
     Equation {
         expression: String,
     },
+    /// A rich text type this crate does not know about yet. Serializes back to the same payload.
+    #[serde(skip)]
+    Unknown {
+        r#type: String,
+        raw: serde_json::Value,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+impl Tagged for RichTextData {
+    const NAME: &'static str = "rich text";
+
+    fn known_types() -> &'static [&'static str] {
+        static TYPES: Lazy<&[&str]> = Lazy::new(|| unknown::variants(RichTextData::deserialize));
+        &TYPES
+    }
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+
+    fn unknown(r#type: String, raw: serde_json::Value) -> Self {
+        Self::Unknown { r#type, raw }
+    }
+}
+
+impl<'de> Deserialize<'de> for RichTextData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        unknown::deserialize(deserializer)
+    }
+}
+
+impl Serialize for RichTextData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown { r#type, raw } => unknown::serialize(r#type, raw, serializer),
+            known => Self::serialize(known, serializer),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct RichText {
     pub r#type: String,
 
@@ -34,20 +121,372 @@ pub struct RichText {
     pub href: Option<String>,
 }
 
+impl<'de> Deserialize<'de> for RichText {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // See `Block`: `data` cannot be flattened.
+        #[derive(Deserialize)]
+        struct Raw {
+            r#type: String,
+            annotations: Annotations,
+            plain_text: String,
+            href: Option<String>,
+            #[serde(flatten)]
+            rest: serde_json::Map<String, serde_json::Value>,
+        }
+
+        let mut raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            data: unknown::from_payload(&raw.r#type, &mut raw.rest)?,
+            r#type: raw.r#type,
+            annotations: raw.annotations,
+            plain_text: raw.plain_text,
+            href: raw.href,
+        })
+    }
+}
+
+/// A mention of a user, page, database, date or link.
+///
+/// [Reference](https://developers.notion.com/reference/rich-text#mention)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Mention {
+    pub r#type: String,
+    #[serde(flatten)]
+    pub data: MentionData,
+}
+
+impl<'de> Deserialize<'de> for Mention {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // See `Block`: `data` cannot be flattened.
+        #[derive(Deserialize)]
+        struct Raw {
+            r#type: String,
+            #[serde(flatten)]
+            rest: serde_json::Map<String, serde_json::Value>,
+        }
+
+        let mut raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            data: unknown::from_payload(&raw.r#type, &mut raw.rest)?,
+            r#type: raw.r#type,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", remote = "Self")]
+pub enum MentionData {
+    Database(Reference<DatabaseId>),
+    Date(Date),
+    LinkPreview(ExternalUrl),
+    Page(Reference<PageId>),
+    TemplateMention(TemplateMention),
+    /// Only the id. The name is in [`RichText::plain_text`].
+    User(PartialUser),
+    /// A mention type this crate does not know about yet. Serializes back to the same payload.
+    #[serde(skip)]
+    Unknown {
+        r#type: String,
+        raw: serde_json::Value,
+    },
+}
+
+impl Tagged for MentionData {
+    const NAME: &'static str = "mention";
+
+    fn known_types() -> &'static [&'static str] {
+        static TYPES: Lazy<&[&str]> = Lazy::new(|| unknown::variants(MentionData::deserialize));
+        &TYPES
+    }
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+
+    fn unknown(r#type: String, raw: serde_json::Value) -> Self {
+        Self::Unknown { r#type, raw }
+    }
+}
+
+impl<'de> Deserialize<'de> for MentionData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        unknown::deserialize(deserializer)
+    }
+}
+
+impl Serialize for MentionData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unknown { r#type, raw } => unknown::serialize(r#type, raw, serializer),
+            known => Self::serialize(known, serializer),
+        }
+    }
+}
+
+/// A placeholder in a template that is filled in when the template is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateMention {
+    TemplateMentionDate {
+        template_mention_date: TemplateMentionDate,
+    },
+    TemplateMentionUser {
+        template_mention_user: TemplateMentionUser,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMentionDate {
+    Today,
+    Now,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMentionUser {
+    Me,
+}
+
 impl RichText {
+    /// Unformatted text without a link.
+    pub fn plain(content: impl Into<String>) -> Self {
+        let content = content.into();
+        Self {
+            r#type: "text".to_string(),
+            data: RichTextData::Text {
+                content: content.clone(),
+                link: None,
+            },
+            annotations: Annotations::default(),
+            plain_text: content,
+            href: None,
+        }
+    }
+
+    /// An inline equation, as a KaTeX expression.
+    pub fn equation(expression: impl Into<String>) -> Self {
+        let expression = expression.into();
+        Self {
+            r#type: "equation".to_string(),
+            data: RichTextData::Equation {
+                expression: expression.clone(),
+            },
+            annotations: Annotations::default(),
+            plain_text: expression,
+            href: None,
+        }
+    }
+
+    /// This span as inline Markdown, with annotations applied and special characters escaped.
     pub fn to_markdown(&self) -> String {
-        use RichTextData::{Equation, Mention, Text};
+        use RichTextData::{Equation, Mention, Text, Unknown};
         match &self.data {
             Text { content, link } => {
-                let mut res = content.clone();
+                let mut res = self.annotations.to_markdown(content);
                 if let Some(link) = link {
-                    res = format!("[{}]({})", res, link.url);
+                    res = format!("[{}]({})", res, markdown::destination(&link.url));
                 }
                 res
             }
-            Equation { expression } => format!("$${expression}$$"),
-            #[allow(clippy::uninlined_format_args)]
-            Mention { r#type } => format!("@{}", r#type), // TODO: include user name
+            Equation { expression } => format!("${expression}$"),
+            Mention(mention) => {
+                // Notion resolves the user name, page title or date into `plain_text`.
+                let href = self.href.clone().or_else(|| match &mention.data {
+                    MentionData::Page(page) => Some(markdown::notion_url(page.id.as_str())),
+                    MentionData::Database(database) => {
+                        Some(markdown::notion_url(database.id.as_str()))
+                    }
+                    MentionData::LinkPreview(link) => Some(link.url.clone()),
+                    _ => None,
+                });
+                let text = self.annotations.to_markdown(&self.plain_text);
+                match href {
+                    Some(href) => format!("[{text}]({})", markdown::destination(&href)),
+                    None => text,
+                }
+            }
+            Unknown { .. } => self.annotations.to_markdown(&self.plain_text),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        Annotations, Link, MentionData, RichText, RichTextData, TemplateMention,
+        TemplateMentionDate,
+    };
+
+    fn raw(mention: serde_json::Value, plain_text: &str, href: Option<&str>) -> serde_json::Value {
+        json!({
+            "type": "mention",
+            "mention": mention,
+            "annotations": {
+                "bold": false, "italic": false, "strikethrough": false,
+                "underline": false, "code": false, "color": "default"
+            },
+            "plain_text": plain_text,
+            "href": href
+        })
+    }
+
+    fn mention(mention: serde_json::Value, plain_text: &str, href: Option<&str>) -> RichText {
+        serde_json::from_value(raw(mention, plain_text, href)).unwrap()
+    }
+
+    fn data(text: &RichText) -> &MentionData {
+        let RichTextData::Mention(mention) = &text.data else {
+            panic!("expected mention");
+        };
+        &mention.data
+    }
+
+    #[test]
+    fn mention_round_trips() {
+        let raw = raw(
+            json!({ "type": "page", "page": { "id": "3c612f56-fdd0-4a30-a4d6-bda7d7426309" } }),
+            "Tuscan kale",
+            Some("https://www.notion.so/3c612f56fdd04a30a4d6bda7d7426309"),
+        );
+        let text: RichText = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&text).unwrap(), raw);
+    }
+
+    #[test]
+    fn mentions() {
+        let user = mention(
+            json!({
+                "type": "user",
+                "user": { "object": "user", "id": "b2e19928-b427-4aad-9a9d-fde65479b1d9" }
+            }),
+            "@Ada Lovelace",
+            None,
+        );
+        assert!(matches!(data(&user), MentionData::User(_)));
+        assert_eq!(user.to_markdown(), "@Ada Lovelace");
+
+        let page = mention(
+            json!({ "type": "page", "page": { "id": "3c612f56-fdd0-4a30-a4d6-bda7d7426309" } }),
+            "Tuscan kale",
+            Some("https://www.notion.so/3c612f56fdd04a30a4d6bda7d7426309"),
+        );
+        assert_eq!(
+            page.to_markdown(),
+            "[Tuscan kale](https://www.notion.so/3c612f56fdd04a30a4d6bda7d7426309)"
+        );
+
+        let database = mention(
+            json!({ "type": "database", "database": { "id": "a1d8501e-1ac1-43e9-a6bd-ea9fe6c8822b" } }),
+            "Grocery list",
+            None,
+        );
+        assert_eq!(
+            database.to_markdown(),
+            "[Grocery list](https://www.notion.so/a1d8501e1ac143e9a6bdea9fe6c8822b)"
+        );
+
+        let date = mention(
+            json!({
+                "type": "date",
+                "date": { "start": "2022-12-16", "end": null, "time_zone": null }
+            }),
+            "2022-12-16",
+            None,
+        );
+        let MentionData::Date(value) = data(&date) else {
+            panic!("expected date");
+        };
+        assert_eq!(value.start, "2022-12-16".parse().unwrap());
+
+        let preview = mention(
+            json!({
+                "type": "link_preview",
+                "link_preview": { "url": "https://workspace.slack.com/archives/C04/p1671" }
+            }),
+            "https://workspace.slack.com/archives/C04/p1671",
+            Some("https://workspace.slack.com/archives/C04/p1671"),
+        );
+        assert!(matches!(data(&preview), MentionData::LinkPreview(_)));
+
+        let url = "https://en.wikipedia.org/wiki/Kale_(disambiguation)";
+        let preview = mention(
+            json!({ "type": "link_preview", "link_preview": { "url": url } }),
+            url,
+            None,
+        );
+        assert_eq!(
+            preview.to_markdown(),
+            format!(r"[https://en.wikipedia.org/wiki/Kale\_(disambiguation)](<{url}>)")
+        );
+
+        let template = mention(
+            json!({
+                "type": "template_mention",
+                "template_mention": {
+                    "type": "template_mention_date",
+                    "template_mention_date": "today"
+                }
+            }),
+            "@Today",
+            None,
+        );
+        assert_eq!(
+            data(&template),
+            &MentionData::TemplateMention(TemplateMention::TemplateMentionDate {
+                template_mention_date: TemplateMentionDate::Today
+            })
+        );
+    }
+
+    #[test]
+    fn annotations_to_markdown() {
+        let styled = |content: &str, annotations: Annotations| RichText {
+            annotations,
+            ..RichText::plain(content)
+        };
+
+        let bold = Annotations {
+            bold: true,
+            ..Annotations::default()
+        };
+        assert_eq!(styled("kale ", bold.clone()).to_markdown(), "**kale** ");
+
+        let all = Annotations {
+            italic: true,
+            strikethrough: true,
+            ..bold
+        };
+        assert_eq!(styled("kale", all).to_markdown(), "***~~kale~~***");
+
+        let code = Annotations {
+            code: true,
+            ..Annotations::default()
+        };
+        assert_eq!(styled("a*b", code.clone()).to_markdown(), "`a*b`");
+        assert_eq!(styled("`x`", code).to_markdown(), "`` `x` ``");
+
+        assert_eq!(RichText::plain("2 * [3]").to_markdown(), r"2 \* \[3\]");
+    }
+
+    #[test]
+    fn links_and_equations_to_markdown() {
+        let link = RichText {
+            data: RichTextData::Text {
+                content: "kale".to_string(),
+                link: Some(Link {
+                    url: "https://en.wikipedia.org/wiki/Kale_(disambiguation)".to_string(),
+                }),
+            },
+            ..RichText::plain("kale")
+        };
+        assert_eq!(
+            link.to_markdown(),
+            "[kale](<https://en.wikipedia.org/wiki/Kale_(disambiguation)>)"
+        );
+
+        assert_eq!(RichText::equation("e^x").to_markdown(), "$e^x$");
+    }
+}
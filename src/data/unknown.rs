@@ -0,0 +1,325 @@
+//! Decoding of enums with a fallback for types Notion added after this crate was written.
+//!
+//! The enums derive `Deserialize` and `Serialize` with `#[serde(remote = "Self")]`, which turns
+//! the derived code into inherent functions. The trait impls check the type against the derived
+//! variant names first and only hand known types to the derived code, so errors inside known
+//! payloads keep their path.
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{
+        self, DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess, VariantAccess,
+        Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::SerializeMap,
+    Deserializer, Serializer,
+};
+
+/// An externally tagged enum, i.e. `{ "<type>": <payload> }`, with an unknown variant.
+pub(crate) trait Tagged: Sized {
+    /// Used in error messages, e.g. `"block"`.
+    const NAME: &'static str;
+
+    /// The derived variant names. Use [`variants`] to get them.
+    fn known_types() -> &'static [&'static str];
+
+    /// The derived `Deserialize`.
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+
+    fn unknown(r#type: String, raw: serde_json::Value) -> Self;
+}
+
+/// The variant names a derived `Deserialize` of an enum accepts.
+pub(crate) fn variants<T>(derived: fn(Probe) -> Result<T, ProbeError>) -> &'static [&'static str] {
+    match derived(Probe) {
+        Err(ProbeError(Some(variants))) => variants,
+        _ => unreachable!("derived enums call deserialize_enum"),
+    }
+}
+
+/// Deserialize `{ "<type>": <payload> }`.
+pub(crate) fn deserialize<'de, T: Tagged, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_map(TaggedVisitor(PhantomData))
+}
+
+/// Deserialize the payload stored under `r#type` in the remaining fields of a struct.
+///
+/// Used by structs whose `type` field would otherwise be consumed before the enum could see it.
+pub(crate) fn from_payload<T: Tagged + de::DeserializeOwned, E: de::Error>(
+    r#type: &str,
+    rest: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<T, E> {
+    let payload = rest.remove(r#type).unwrap_or_default();
+    if !T::known_types().contains(&r#type) {
+        return Ok(T::unknown(r#type.to_string(), payload));
+    }
+
+    // Errors are passed on as messages, so the path inside the payload is added to them. The
+    // path starts with the type, e.g. `multi_select[0].name`.
+    let tagged = serde_json::Value::Object([(r#type.to_string(), payload)].into_iter().collect());
+    serde_path_to_error::deserialize::<_, T>(tagged)
+        .map_err(|err| E::custom(format_args!("{}: {}", err.path(), err.inner())))
+}
+
+/// Serialize `{ "<type>": <raw> }`.
+pub(crate) fn serialize<S: Serializer>(
+    r#type: &str,
+    raw: &serde_json::Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(r#type, raw)?;
+    map.end()
+}
+
+struct TaggedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Tagged> Visitor<'de> for TaggedVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} with a single type key", T::NAME)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let Some(tag) = map.next_key::<String>()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+
+        let value = if T::known_types().contains(&tag.as_str()) {
+            T::deserialize_known(Variant { tag, map: &mut map })?
+        } else {
+            T::unknown(tag, map.next_value()?)
+        };
+
+        if map.next_key::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(value)
+    }
+}
+
+/// Replays a tag that was already read from `map` to the derived `Deserialize`.
+struct Variant<'a, A> {
+    tag: String,
+    map: &'a mut A,
+}
+
+impl<'de, 'a, A: MapAccess<'de>> Deserializer<'de> for Variant<'a, A> {
+    type Error = A::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, A::Error> {
+        visitor.visit_enum(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, 'a, A: MapAccess<'de>> EnumAccess<'de> for Variant<'a, A> {
+    type Error = A::Error;
+    type Variant = VariantValue<'a, A>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), A::Error> {
+        let variant = seed.deserialize(self.tag.into_deserializer())?;
+        Ok((variant, VariantValue(self.map)))
+    }
+}
+
+struct VariantValue<'a, A>(&'a mut A);
+
+impl<'de, 'a, A: MapAccess<'de>> VariantAccess<'de> for VariantValue<'a, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.0.next_value::<IgnoredAny>().map(drop)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        self.0.next_value_seed(seed)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, A::Error> {
+        self.0.next_value_seed(TupleSeed(len, visitor))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.0.next_value_seed(StructSeed(fields, visitor))
+    }
+}
+
+struct TupleSeed<V>(usize, V);
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for TupleSeed<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_tuple(self.0, self.1)
+    }
+}
+
+struct StructSeed<V>(&'static [&'static str], V);
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for StructSeed<V> {
+    type Value = V::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_struct("", self.0, self.1)
+    }
+}
+
+/// A deserializer that only records the variant names passed to `deserialize_enum`.
+pub(crate) struct Probe;
+
+#[derive(Debug)]
+pub(crate) struct ProbeError(Option<&'static [&'static str]>);
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("probe")
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl de::Error for ProbeError {
+    fn custom<T: fmt::Display>(_: T) -> Self {
+        Self(None)
+    }
+}
+
+impl<'de> Deserializer<'de> for Probe {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, ProbeError> {
+        Err(ProbeError(None))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        variants: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, ProbeError> {
+        Err(ProbeError(Some(variants)))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::data::{Block, BlockData, Color, Property, PropertyData, RichText, RichTextData};
+
+    #[test]
+    fn unknown_block_round_trips() {
+        let payload = json!({ "rich_text": [], "color": "teal", "frobnicate": true });
+        let raw = json!({
+            "object": "block",
+            "id": "c02fc1d3-db8b-45c5-a222-27595b15aea7",
+            "parent": { "type": "page_id", "page_id": "59833787-2cf9-4fdf-8782-e53db20768a5" },
+            "type": "hologram",
+            "hologram": payload,
+            "created_time": "2022-03-01T19:05:00.000Z",
+            "created_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "last_edited_time": "2022-03-01T19:05:00.000Z",
+            "last_edited_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "archived": false,
+            "has_children": false
+        });
+
+        let block: Block = serde_json::from_value(raw.clone()).unwrap();
+        let BlockData::Unknown { r#type, raw: data } = &block.data else {
+            panic!("expected unknown block, got {:?}", block.data);
+        };
+        assert_eq!(r#type, "hologram");
+        assert_eq!(data, &payload);
+
+        let mut expected = raw;
+        expected.as_object_mut().unwrap().remove("object");
+        assert_eq!(serde_json::to_value(&block).unwrap(), expected);
+    }
+
+    #[test]
+    fn unknown_property_and_rich_text_round_trip() {
+        let raw = json!({ "id": "a%3Cb", "type": "place", "place": { "lat": 52.5 } });
+        let property: Property = serde_json::from_value(raw).unwrap();
+        assert!(
+            matches!(&property.data, PropertyData::Unknown { r#type, .. } if r#type == "place")
+        );
+        assert_eq!(
+            serde_json::to_value(&property.data).unwrap(),
+            json!({ "place": { "lat": 52.5 } })
+        );
+
+        let raw = json!({
+            "type": "emoji",
+            "emoji": { "emoji": "🥬" },
+            "annotations": {
+                "bold": false, "italic": false, "strikethrough": false,
+                "underline": false, "code": false, "color": "default"
+            },
+            "plain_text": "🥬",
+            "href": null
+        });
+        let text: RichText = serde_json::from_value(raw.clone()).unwrap();
+        assert!(matches!(&text.data, RichTextData::Unknown { r#type, .. } if r#type == "emoji"));
+        assert_eq!(text.to_markdown(), "🥬");
+        assert_eq!(serde_json::to_value(&text).unwrap(), raw);
+    }
+
+    #[test]
+    fn unknown_color_round_trips() {
+        let color: Color = serde_json::from_value(json!("teal_background")).unwrap();
+        assert_eq!(color, Color::Unknown("teal_background".to_string()));
+        assert_eq!(
+            serde_json::to_value(&color).unwrap(),
+            json!("teal_background")
+        );
+
+        let color: Color = serde_json::from_value(json!("blue_background")).unwrap();
+        assert_eq!(color, Color::BlueBackground);
+    }
+
+    #[test]
+    fn known_types_still_fail_loudly() {
+        let err = serde_json::from_value::<BlockData>(json!({ "paragraph": { "rich_text": 1 } }))
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid type"), "{err}");
+    }
+
+    #[test]
+    fn payload_errors_keep_their_path() {
+        let raw = json!({
+            "id": "a%3Cb",
+            "type": "multi_select",
+            "multi_select": [{ "id": "c", "name": 5, "color": "red" }]
+        });
+        let err = serde_json::from_value::<Property>(raw).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("multi_select[0].name: invalid type"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use crate::UserId;
+
+/// A person or bot in the workspace.
+///
+/// [Reference](https://developers.notion.com/reference/user)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum User {
+    Person {
+        id: UserId,
+        name: Option<String>,
+        avatar_url: Option<String>,
+        person: Person,
+    },
+    Bot {
+        id: UserId,
+        name: Option<String>,
+        avatar_url: Option<String>,
+        bot: Bot,
+    },
+}
+
+impl User {
+    #[must_use]
+    pub const fn id(&self) -> &UserId {
+        match self {
+            Self::Person { id, .. } | Self::Bot { id, .. } => id,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Person { name, .. } | Self::Bot { name, .. } => name.as_deref(),
+        }
+    }
+
+    #[must_use]
+    pub fn avatar_url(&self) -> Option<&str> {
+        match self {
+            Self::Person { avatar_url, .. } | Self::Bot { avatar_url, .. } => avatar_url.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Person {
+    /// Only present if the integration has the capability to read email addresses.
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// An integration. Only the bot of the integration making the request has an owner and
+/// workspace name, see [`Client::me`](crate::Client::me).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bot {
+    #[serde(default)]
+    pub owner: Option<BotOwner>,
+    #[serde(default)]
+    pub workspace_name: Option<String>,
+}
+
+/// Who installed the integration.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotOwner {
+    /// An internal integration, owned by the workspace. Always `true`.
+    Workspace { workspace: bool },
+    /// A public integration, owned by the user who authorized it.
+    User { user: Box<MaybePartialUser> },
+}
+
+/// A user by id only, as in `created_by` and `last_edited_by`.
+///
+/// Full users decode into this as well, keeping only the id.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "object", rename = "user")]
+pub struct PartialUser {
+    pub id: UserId,
+}
+
+/// A user in a people property or as the owner of a bot.
+///
+/// Notion only sends the id if the integration cannot read user information, or for users
+/// that left the workspace.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MaybePartialUser {
+    Full(User),
+    Partial(PartialUser),
+}
+
+impl MaybePartialUser {
+    #[must_use]
+    pub const fn id(&self) -> &UserId {
+        match self {
+            Self::Full(user) => user.id(),
+            Self::Partial(user) => &user.id,
+        }
+    }
+
+    /// `None` for partial users.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Full(user) => user.name(),
+            Self::Partial(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_full(&self) -> Option<&User> {
+        match self {
+            Self::Full(user) => Some(user),
+            Self::Partial(_) => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MaybePartialUser {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Not `#[serde(untagged)]`, which would hide why a full user failed to decode.
+        let raw = serde_json::Map::deserialize(deserializer)?;
+        let res = if raw.contains_key("type") {
+            serde_json::from_value(raw.into()).map(Self::Full)
+        } else {
+            serde_json::from_value(raw.into()).map(Self::Partial)
+        };
+        res.map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Bot, BotOwner, MaybePartialUser, PartialUser, User};
+
+    #[test]
+    fn deser_people_and_bots() {
+        let person: User = serde_json::from_value(json!({
+            "object": "user",
+            "id": "c2f20311-9e54-4d11-8c79-7398424ae41e",
+            "name": "Ada",
+            "avatar_url": null,
+            "type": "person",
+            "person": {}
+        }))
+        .unwrap();
+        let User::Person { person, .. } = &person else {
+            panic!("expected person");
+        };
+        assert_eq!(person.email, None);
+
+        let bot: User = serde_json::from_value(json!({
+            "object": "user",
+            "id": "9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57",
+            "name": "Pantry sync",
+            "avatar_url": null,
+            "type": "bot",
+            "bot": {
+                "owner": { "type": "workspace", "workspace": true },
+                "workspace_name": "Kitchen"
+            }
+        }))
+        .unwrap();
+        assert_eq!(bot.name(), Some("Pantry sync"));
+        assert_eq!(
+            bot,
+            User::Bot {
+                id: "9a3b5ae0-c6e6-482d-b0e1-ed315ee6dc57".parse().unwrap(),
+                name: Some("Pantry sync".to_string()),
+                avatar_url: None,
+                bot: Bot {
+                    owner: Some(BotOwner::Workspace { workspace: true }),
+                    workspace_name: Some("Kitchen".to_string()),
+                },
+            }
+        );
+
+        let other: User = serde_json::from_value(json!({
+            "object": "user",
+            "id": "1c5bb5c0-6b1e-4b8f-9f0b-8f2d7c4c2a11",
+            "name": "Another integration",
+            "avatar_url": null,
+            "type": "bot",
+            "bot": {}
+        }))
+        .unwrap();
+        assert!(matches!(other, User::Bot { bot, .. } if bot == Bot::default()));
+    }
+
+    #[test]
+    fn deser_partial_user() {
+        let raw = json!({ "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" });
+        let user: PartialUser = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(user.id, "ee5f0f84-409a-440f-983a-a5315961c6e4");
+        assert_eq!(serde_json::to_value(&user).unwrap(), raw);
+        assert!(serde_json::from_value::<User>(json!({ "id": user.id })).is_err());
+    }
+
+    #[test]
+    fn deser_maybe_partial_users() {
+        let raw = json!({ "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" });
+        let user: MaybePartialUser = serde_json::from_value(raw.clone()).unwrap();
+        assert!(matches!(&user, MaybePartialUser::Partial(_)));
+        assert_eq!(user.id(), "ee5f0f84-409a-440f-983a-a5315961c6e4");
+        assert_eq!(user.name(), None);
+        assert_eq!(serde_json::to_value(&user).unwrap(), raw);
+
+        let owner: BotOwner =
+            serde_json::from_value(json!({ "type": "user", "user": raw })).unwrap();
+        assert!(matches!(owner, BotOwner::User { user } if user.as_full().is_none()));
+
+        let err = serde_json::from_value::<MaybePartialUser>(json!({
+            "object": "user",
+            "id": "ee5f0f84-409a-440f-983a-a5315961c6e4",
+            "type": "person",
+            "person": { "email": 5 }
+        }))
+        .unwrap_err();
+        assert!(err.to_string().starts_with("invalid type"), "{err}");
+    }
+}
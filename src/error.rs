@@ -0,0 +1,315 @@
+//! <https://developers.notion.com/reference/status-codes>
+
+use std::fmt;
+
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    data::{Block, BlockData},
+    BlockId,
+};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The request could not be sent or the response body could not be read.
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// A header configured on the [`ClientBuilder`](crate::ClientBuilder) is not valid.
+    #[error("invalid header value: {0}")]
+    InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
+
+    /// Notion responded with an error object.
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    /// The response body did not have the expected shape.
+    #[error("failed to decode response at `{path}`: {source}")]
+    Decode {
+        /// The path to the value that failed, e.g. `results[3].paragraph.color`.
+        path: serde_path_to_error::Path,
+        source: serde_json::Error,
+    },
+
+    /// A block in a list of children could not be decoded.
+    #[error(transparent)]
+    Block(#[from] BlockDecodeError),
+
+    /// The [`RateLimit`](crate::RateLimit) given to the
+    /// [`ClientBuilder`](crate::ClientBuilder) does not allow any requests.
+    #[error("rate limit must allow more than 0 requests per second, got {requests_per_second}")]
+    InvalidRateLimit { requests_per_second: f64 },
+
+    /// The children of a new block changed before the blocks nested in them could be appended.
+    /// See [`Client::append_block_children`](crate::Client::append_block_children).
+    #[error("children of block {block_id} changed while appending to them")]
+    ChildrenChanged { block_id: BlockId },
+
+    /// The response decoded fine but was not the kind of object that was asked for.
+    #[error("expected {expected} object, got {found}")]
+    UnexpectedObject {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl Error {
+    /// The HTTP status, if Notion responded with an error.
+    #[must_use]
+    pub const fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Api(err) => Some(err.status),
+            _ => None,
+        }
+    }
+
+    /// The Notion error code, if Notion responded with an error.
+    #[must_use]
+    pub const fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::Api(err) => Some(err.code),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(err: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = err.path().clone();
+        Self::Decode {
+            path,
+            source: err.into_inner(),
+        }
+    }
+}
+
+/// An error object returned by the API.
+///
+/// ```json
+/// {
+///   "object": "error",
+///   "status": 404,
+///   "code": "object_not_found",
+///   "message": "Could not find page with ID: ...",
+///   "request_id": "..."
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: ErrorCode,
+    pub message: String,
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    /// Build an error from a non-success response.
+    ///
+    /// Bodies that are not Notion error objects (e.g. an HTML page from a proxy) keep the raw body
+    /// as the message and get [`ErrorCode::Unknown`].
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            code: ErrorCode,
+            message: String,
+            request_id: Option<String>,
+        }
+
+        match serde_json::from_slice::<Body>(body) {
+            Ok(Body {
+                code,
+                message,
+                request_id,
+            }) => Self {
+                status,
+                code,
+                message,
+                request_id,
+            },
+            Err(_) => Self {
+                status,
+                code: ErrorCode::Unknown,
+                message: String::from_utf8_lossy(body).into_owned(),
+                request_id: None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?}): {}", self.status, self.code, self.message)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request_id: {request_id}]")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// A single block that could not be decoded.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "failed to decode block {} at `{path}`: {source}",
+    block_id.as_deref().unwrap_or("<unknown id>")
+)]
+pub struct BlockDecodeError {
+    /// The `id` of the block, if the raw JSON had one.
+    pub block_id: Option<String>,
+    /// The path inside the block that failed, e.g. `callout.rich_text`.
+    pub path: serde_path_to_error::Path,
+    /// The raw JSON of the block.
+    pub raw: serde_json::Value,
+    pub source: serde_json::Error,
+}
+
+/// <https://developers.notion.com/reference/status-codes#error-codes>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// 400
+    InvalidJson,
+    /// 400
+    InvalidRequestUrl,
+    /// 400
+    InvalidRequest,
+    /// 400
+    InvalidGrant,
+    /// 400
+    ValidationError,
+    /// 400
+    MissingVersion,
+    /// 401
+    Unauthorized,
+    /// 403
+    RestrictedResource,
+    /// 404
+    ObjectNotFound,
+    /// 409
+    ConflictError,
+    /// 429
+    RateLimited,
+    /// 500
+    InternalServerError,
+    /// 502
+    BadGateway,
+    /// 503
+    ServiceUnavailable,
+    /// 503
+    DatabaseConnectionUnavailable,
+    /// 504
+    GatewayTimeout,
+    /// A code this crate does not know about, or a body that was not an error object.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Decode a JSON body, keeping track of where decoding failed.
+pub(crate) fn decode<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    let de = &mut serde_json::Deserializer::from_slice(body);
+    let value = serde_path_to_error::deserialize(de)?;
+    Ok(value)
+}
+
+/// Decode a single block from the `results` of a list response.
+pub(crate) fn decode_block(raw: serde_json::Value) -> Result<Block, BlockDecodeError> {
+    let err = match serde_path_to_error::deserialize(&raw) {
+        Ok(block) => return Ok(block),
+        Err(err) => err,
+    };
+
+    // `BlockData` is flattened into `Block`, which hides the path inside the payload. Decode the
+    // payload on its own to find out where it actually failed.
+    let payload_err = raw
+        .get("type")
+        .and_then(serde_json::Value::as_str)
+        .and_then(|ty| {
+            let payload = raw.get(ty)?;
+            let data = serde_json::json!({ ty: payload });
+            serde_path_to_error::deserialize::<_, BlockData>(&data).err()
+        });
+    let err = payload_err.unwrap_or(err);
+
+    let block_id = raw
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string);
+
+    Err(BlockDecodeError {
+        block_id,
+        path: err.path().clone(),
+        raw,
+        source: err.into_inner(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::{decode, decode_block, ApiError, Error, ErrorCode};
+    use crate::data::Page;
+
+    #[test]
+    fn parse_error_body() {
+        let body = br#"{
+            "object": "error",
+            "status": 404,
+            "code": "object_not_found",
+            "message": "Could not find page with ID: 59833787-2cf9-4fdf-8782-e53db20768a5.",
+            "request_id": "cf0b3c5e-6a0f-4bb0-a4bb-2d4c0b4a2f48"
+        }"#;
+
+        let err = ApiError::from_response(StatusCode::NOT_FOUND, body);
+        assert_eq!(err.code, ErrorCode::ObjectNotFound);
+        assert_eq!(
+            err.request_id.as_deref(),
+            Some("cf0b3c5e-6a0f-4bb0-a4bb-2d4c0b4a2f48")
+        );
+    }
+
+    #[test]
+    fn parse_non_json_error_body() {
+        let err = ApiError::from_response(StatusCode::BAD_GATEWAY, b"<html>bad gateway</html>");
+        assert_eq!(err.code, ErrorCode::Unknown);
+        assert_eq!(err.message, "<html>bad gateway</html>");
+    }
+
+    #[test]
+    fn decode_error_has_path() {
+        let body = br#"{ "id": "59833787-2cf9-4fdf-8782-e53db20768a5", "created_time": 5 }"#;
+
+        let Err(Error::Decode { path, .. }) = decode::<Page>(body) else {
+            panic!("expected decode error");
+        };
+        assert_eq!(path.to_string(), "created_time");
+    }
+
+    #[test]
+    fn block_decode_error_names_block() {
+        let raw = serde_json::json!({
+            "object": "block",
+            "id": "c02fc1d3-db8b-45c5-a222-27595b15aea7",
+            "type": "heading_1",
+            "heading_1": { "rich_text": [], "color": "default", "is_toggleable": "no" },
+            "created_time": "2022-03-01T19:05:00.000Z",
+            "created_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "last_edited_time": "2022-03-01T19:05:00.000Z",
+            "last_edited_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "archived": false,
+            "has_children": false
+        });
+
+        let err = decode_block(raw.clone()).unwrap_err();
+        assert_eq!(
+            err.block_id.as_deref(),
+            Some("c02fc1d3-db8b-45c5-a222-27595b15aea7")
+        );
+        assert_eq!(err.path.to_string(), "heading_1.is_toggleable");
+        assert_eq!(err.raw, raw);
+    }
+}
@@ -0,0 +1,317 @@
+//! Typed ids, so that a page id cannot be passed where a user id is expected.
+//!
+//! Every id parses from the hyphenated form the API returns, the 32 character form without
+//! hyphens, and Notion URLs like `https://www.notion.so/workspace/Title-598337872cf94fdf8782e53db20768a5`.
+//! They are displayed and serialized in the hyphenated form.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An id that could not be parsed.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid Notion id: {input:?}")]
+pub struct ParseIdError {
+    pub input: String,
+}
+
+/// How to handle the fragment of a URL, which links to a block on the page.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Fragment {
+    Ignore,
+    Prefer,
+    Require,
+}
+
+macro_rules! id {
+    ($(#[$meta:meta])* $name:ident, $fragment:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            /// The hyphenated form, e.g. `59833787-2cf9-4fdf-8782-e53db20768a5`.
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Skips validation for ids that are known to be valid, e.g. because they are the
+            /// same as an id of another kind.
+            #[allow(dead_code)]
+            pub(crate) fn from_valid(id: impl fmt::Display) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIdError;
+
+            fn from_str(input: &str) -> Result<Self, ParseIdError> {
+                parse(input, $fragment).map(Self)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = ParseIdError;
+
+            fn try_from(input: &str) -> Result<Self, ParseIdError> {
+                input.parse()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let id = String::deserialize(deserializer)?;
+                id.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+id! {
+    /// The id of a page, which is also the id of its `child_page` block.
+    PageId,
+    Fragment::Ignore
+}
+
+id! {
+    /// The id of a block.
+    ///
+    /// Pages and databases are blocks as well, so [`PageId`] and [`DatabaseId`] convert into
+    /// block ids and can be passed to the block methods, e.g. to get the content of a page with
+    /// [`Client::block_children`](crate::Client::block_children). See [`AsBlockId`].
+    ///
+    /// Parsing a URL that links to a block uses the block in the fragment, not the page.
+    BlockId,
+    Fragment::Prefer
+}
+
+id! {
+    /// The id of a database, which is also the id of its `child_database` block.
+    DatabaseId,
+    Fragment::Ignore
+}
+
+id! {
+    /// The id of a user or bot.
+    UserId,
+    Fragment::Ignore
+}
+
+impl BlockId {
+    /// Like [`FromStr`], but URLs without a block in the fragment are an error instead of
+    /// returning the page.
+    ///
+    /// # Errors
+    /// If `input` is not a valid id or URL.
+    pub fn parse_block_link(input: &str) -> Result<Self, ParseIdError> {
+        parse(input, Fragment::Require).map(Self)
+    }
+}
+
+/// An id that is also the id of a block, so the block methods of [`Client`](crate::Client)
+/// take a [`PageId`] or [`DatabaseId`] as well.
+pub trait AsBlockId: fmt::Display + fmt::Debug {
+    fn to_block_id(&self) -> BlockId;
+}
+
+impl AsBlockId for BlockId {
+    fn to_block_id(&self) -> BlockId {
+        self.clone()
+    }
+}
+
+impl AsBlockId for PageId {
+    fn to_block_id(&self) -> BlockId {
+        self.into()
+    }
+}
+
+impl AsBlockId for DatabaseId {
+    fn to_block_id(&self) -> BlockId {
+        self.into()
+    }
+}
+
+impl From<PageId> for BlockId {
+    fn from(id: PageId) -> Self {
+        Self(id.0)
+    }
+}
+
+impl From<&PageId> for BlockId {
+    fn from(id: &PageId) -> Self {
+        Self(id.0.clone())
+    }
+}
+
+impl From<DatabaseId> for BlockId {
+    fn from(id: DatabaseId) -> Self {
+        Self(id.0)
+    }
+}
+
+impl From<&DatabaseId> for BlockId {
+    fn from(id: &DatabaseId) -> Self {
+        Self(id.0.clone())
+    }
+}
+
+/// The hyphenated, lowercase form of `input`.
+fn parse(input: &str, fragment: Fragment) -> Result<String, ParseIdError> {
+    let err = || ParseIdError {
+        input: input.to_string(),
+    };
+
+    let input = input.trim();
+    let candidate = if input.contains('/') {
+        let (path, block) = input.split_once('#').unwrap_or((input, ""));
+        match fragment {
+            Fragment::Ignore => {}
+            Fragment::Prefer if block.is_empty() => {}
+            Fragment::Prefer | Fragment::Require => return normalize(block).ok_or_else(err),
+        }
+
+        let path = path.split('?').next().unwrap_or_default();
+        path.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+    } else if fragment == Fragment::Require {
+        return Err(err());
+    } else {
+        input
+    };
+
+    normalize(candidate)
+        .or_else(|| {
+            // `Page-Title-<id>` in URLs.
+            let start = candidate.len().checked_sub(32)?;
+            normalize(candidate.get(start..)?)
+        })
+        .ok_or_else(err)
+}
+
+/// The hyphenated, lowercase form of a hyphenated or 32 character id.
+fn normalize(id: &str) -> Option<String> {
+    let hex: String = match id.len() {
+        32 => id.to_string(),
+        36 => {
+            let groups: Vec<_> = id.split('-').collect();
+            let lengths: Vec<_> = groups.iter().map(|group| group.len()).collect();
+            if lengths != [8, 4, 4, 4, 12] {
+                return None;
+            }
+            groups.concat()
+        }
+        _ => return None,
+    };
+
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let hex = hex.to_ascii_lowercase();
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockId, DatabaseId, PageId, UserId};
+
+    const ID: &str = "59833787-2cf9-4fdf-8782-e53db20768a5";
+
+    #[test]
+    fn parses_every_form() {
+        for input in [
+            ID,
+            "598337872cf94fdf8782e53db20768a5",
+            "598337872CF94FDF8782E53DB20768A5",
+            " 59833787-2cf9-4fdf-8782-e53db20768a5\n",
+            "https://www.notion.so/598337872cf94fdf8782e53db20768a5",
+            "https://www.notion.so/workspace/Tuscan-kale-598337872cf94fdf8782e53db20768a5",
+            "https://www.notion.so/workspace/Tuscan-kale-598337872cf94fdf8782e53db20768a5?pvs=4",
+            "https://www.notion.so/598337872cf94fdf8782e53db20768a5?v=0a1b2c3d4e5f4a6b9c7d8e9fa0b1c2d5",
+            "https://acme.notion.site/59833787-2cf9-4fdf-8782-e53db20768a5/",
+        ] {
+            let id: PageId = input.parse().unwrap_or_else(|err| panic!("{err}"));
+            assert_eq!(id.to_string(), ID);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_ids() {
+        for input in [
+            "",
+            "title",
+            "598337872cf94fdf8782e53db20768a",
+            "598337872cf94fdf8782e53db20768az",
+            "5983378-72cf9-4fdf-8782-e53db20768a5",
+            "https://www.notion.so/workspace/Tuscan-kale",
+        ] {
+            assert!(input.parse::<UserId>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn block_links_use_the_fragment() {
+        let url = "https://www.notion.so/Tuscan-kale-598337872cf94fdf8782e53db20768a5#c02fc1d3db8b45c5a22227595b15aea7";
+        let block: BlockId = url.parse().unwrap();
+        assert_eq!(block, "c02fc1d3-db8b-45c5-a222-27595b15aea7");
+
+        assert_eq!(url.parse::<PageId>().unwrap(), ID);
+        assert_eq!(
+            BlockId::parse_block_link(url).unwrap(),
+            "c02fc1d3-db8b-45c5-a222-27595b15aea7"
+        );
+
+        let page = "https://www.notion.so/Tuscan-kale-598337872cf94fdf8782e53db20768a5";
+        assert_eq!(BlockId::from(page.parse::<PageId>().unwrap()), ID);
+        assert!(BlockId::parse_block_link(page).is_err());
+    }
+
+    #[test]
+    fn serde() {
+        let id: DatabaseId = serde_json::from_value(serde_json::json!(ID)).unwrap();
+        assert_eq!(serde_json::to_value(&id).unwrap(), serde_json::json!(ID));
+        assert!(serde_json::from_value::<DatabaseId>(serde_json::json!("nope")).is_err());
+    }
+}
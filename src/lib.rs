@@ -1,17 +1,37 @@
 //! - [Page tutorial](https://developers.notion.com/docs/working-with-page-content)
 //!     - Covers content vs properties
-use std::format as f;
+use std::{format as f, sync::Arc, time::Duration};
 
-use anyhow::{bail, Result};
+use async_recursion::async_recursion;
+use futures::{future, Stream, TryStreamExt};
 use iter_tools::Itertools;
-use reqwest::{Method, RequestBuilder};
+use reqwest::{header::HeaderMap, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use tokio::sync::Semaphore;
 use tracing::instrument;
 
-use crate::data::SearchResponse;
-pub use crate::utils::CachedClient;
+pub use crate::{
+    builder::{ClientBuilder, DEFAULT_BASE_URL, DEFAULT_NOTION_VERSION},
+    error::{ApiError, BlockDecodeError, Error, ErrorCode, Result},
+    id::{AsBlockId, BlockId, DatabaseId, PageId, ParseIdError, UserId},
+    pagination::CollectAll,
+    retry::{RateLimit, RetryPolicy},
+    utils::CachedClient,
+};
+use crate::{
+    data::SearchResponse,
+    pagination::{join_property_items, paginate, Chunk, PropertyItems},
+    retry::RateLimiter,
+};
 
+mod builder;
 pub mod data;
+mod error;
+mod id;
+pub mod markdown;
+mod pagination;
 pub mod query;
+mod retry;
 mod utils;
 
 fn default<T: Default>() -> T {
@@ -23,46 +43,108 @@ fn default<T: Default>() -> T {
 #[derive(Clone)]
 pub struct Client {
     req: reqwest::Client,
-    integration_token: String,
+    base_url: String,
+    /// Sent with every request, including `Authorization`.
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    retry: RetryPolicy,
+    /// Shared between clones so they all count towards the same limit.
+    limiter: Option<Arc<RateLimiter>>,
 }
 
 impl Client {
-    /// Create a new client with the given integration token.
+    /// Create a new client with the given integration token and default settings.
+    ///
+    /// Requests are limited to [`RateLimit::default`] and retried according to
+    /// [`RetryPolicy::default`].
+    ///
+    /// # Panics
+    /// - If the integration token is not a valid header value.
+    /// - If the TLS backend cannot be initialized, like [`reqwest::Client::new`].
     pub fn new(integration_token: impl Into<String>) -> Self {
-        Self {
-            req: reqwest::Client::new(),
-            integration_token: integration_token.into(),
-        }
+        Self::builder(integration_token)
+            .build()
+            .expect("failed to build client")
+    }
+
+    /// Configure a client with the given integration token.
+    pub fn builder(integration_token: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(integration_token.into())
     }
 
     fn request(&self, method: Method, url: &str) -> RequestBuilder {
-        self.req
-            .request(method, f!("https://api.notion.com/v1/{url}"))
-            .header("Notion-Version", "2022-06-28")
-            .header("Content-Type", "application/json")
-            .bearer_auth(&self.integration_token)
+        let request = self
+            .req
+            .request(method, f!("{}{url}", self.base_url))
+            .headers(self.headers.clone());
+
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
     }
 
+    /// Send the request and decode the response body.
+    ///
+    /// Waits for the rate limiter before every attempt and retries according to the
+    /// [`RetryPolicy`]. Non-success responses are turned into [`Error::Api`].
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let mut request = request.build()?;
+        let mut attempt = 0;
+
+        let response = loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire().await;
+            }
+
+            // JSON bodies can always be cloned
+            let retry = request.try_clone();
+            let method = request.method().clone();
+            let response = self.req.execute(request).await?;
+
+            let delay = self
+                .retry
+                .delay(attempt, &method, response.status(), response.headers());
+
+            match (delay, retry) {
+                (Some(delay), Some(retry)) => {
+                    tracing::debug!(status = %response.status(), ?delay, attempt, "retrying");
+                    tokio::time::sleep(delay).await;
+                    request = retry;
+                    attempt += 1;
+                }
+                _ => break response,
+            }
+        };
+
+        let status = response.status();
+        let body = response.bytes().await?;
+
+        if !status.is_success() {
+            return Err(ApiError::from_response(status, &body).into());
+        }
+
+        error::decode(&body)
+    }
+
+    /// Only returns the first page of users. Use [`Client::list_users_stream`] to get all of them.
+    ///
     /// # Errors
     /// - If the request fails.
+    /// - If the response is not a list of users.
     #[instrument(skip(self))]
     pub async fn list_users(&self) -> Result<Vec<data::User>> {
-        let response = self
-            .request(Method::GET, "users")
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response = self.send(self.request(Method::GET, "users")).await?;
 
-        let data::Object::List(data::List { results }) = response else {
-            bail!("Result {response:?} is not a list")
+        let data::Object::List(data::List { results, .. }) = response else {
+            return Err(unexpected("list", &response));
         };
 
         let res: Vec<_> = results
             .into_iter()
             .map(|result| {
                 let data::Object::User(user) = result else {
-                    bail!("Result {result:?} is not a user")
+                    return Err(unexpected("user", &result));
                 };
                 Ok(user)
             })
@@ -71,115 +153,825 @@ impl Client {
         Ok(res)
     }
 
+    /// Every user in the workspace, following pagination lazily.
+    ///
+    /// Each item fails if the request for its page fails.
+    pub fn list_users_stream(&self) -> impl Stream<Item = Result<data::User>> + '_ {
+        paginate(move |start_cursor| {
+            let query = query::ListUsers {
+                start_cursor,
+                ..default()
+            };
+            self.send(self.request(Method::GET, "users").query(&query))
+        })
+    }
+
     /// # Errors
     /// - If the request fails.
-    #[instrument(skip(self), fields(id = %block_id))]
-    pub async fn block(&self, block_id: &str) -> Result<data::Block> {
-        let response = self
-            .request(Method::GET, &f!("blocks/{block_id}"))
-            .send()
-            .await?
-            .json()
+    /// - If the response is not a user.
+    #[instrument(skip(self), fields(user_id = %user_id))]
+    pub async fn get_user(&self, user_id: &UserId) -> Result<data::User> {
+        let response: data::Object = self
+            .send(self.request(Method::GET, &f!("users/{user_id}")))
             .await?;
 
-        Ok(response)
+        into_user(response)
     }
 
+    /// The bot of the integration whose token is used, including its owner and workspace name.
+    ///
     /// # Errors
     /// - If the request fails.
-    /// - If the response is not a list.
-    /// - If the response is not a block.
+    /// - If the response is not a user.
+    #[instrument(skip(self))]
+    pub async fn me(&self) -> Result<data::User> {
+        let response: data::Object = self.send(self.request(Method::GET, "users/me")).await?;
+
+        into_user(response)
+    }
+
+    /// # Errors
+    /// - If the request fails.
+    #[instrument(skip(self), fields(id = %block_id))]
+    pub async fn block(&self, block_id: &impl AsBlockId) -> Result<data::Block> {
+        self.send(self.request(Method::GET, &f!("blocks/{block_id}")))
+            .await
+    }
+
+    /// Only returns the page of children selected by `query`. Use [`Client::block_children_stream`]
+    /// to get all of them.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    /// - If any of the children cannot be decoded. Use [`Client::block_children_lenient`] to keep
+    ///   the children that did decode.
     #[instrument(skip(self))]
     pub async fn block_children(
         &self,
-        block_id: &str,
+        block_id: &impl AsBlockId,
         query: query::BlockChildren,
     ) -> Result<Vec<data::Block>> {
-        let response: Result<data::Object, _> = self
-            .request(Method::GET, &f!("blocks/{block_id}/children"))
-            .query(&query)
-            .send()
-            .await?
-            .json()
-            .await;
+        let Chunk { results, .. } = self.block_children_raw(block_id, &query).await?;
 
-        let Ok(response) = response else {
-            // TODO: this is jank. Fix.
-            return Ok(vec![]);
-        };
+        let res: Vec<_> = results.into_iter().map(error::decode_block).try_collect()?;
+        Ok(res)
+    }
 
-        let data::Object::List(data::List { results }) = response else {
-            bail!("Result {response:?} is not a list")
-        };
+    /// All children of a block, following pagination lazily.
+    ///
+    /// Each item fails if the request for its page fails or if the block cannot be decoded.
+    pub fn block_children_stream<'a>(
+        &'a self,
+        block_id: &'a impl AsBlockId,
+    ) -> impl Stream<Item = Result<data::Block>> + 'a {
+        paginate(move |start_cursor| {
+            let query = query::BlockChildren {
+                start_cursor,
+                ..default()
+            };
+            async move { self.block_children_raw(block_id, &query).await }
+        })
+        .and_then(|raw| async move { Ok(error::decode_block(raw)?) })
+    }
 
-        let res: Vec<_> = results
-            .into_iter()
-            .map(|result| {
-                let data::Object::Block(block) = result else {
-                    bail!("Result {result:?} is not a block")
-                };
-                Ok(*block)
+    /// All children of a block, with their children filled in recursively according to
+    /// `options`.
+    ///
+    /// # Errors
+    /// - If any of the requests fails.
+    /// - If any of the blocks cannot be decoded.
+    #[instrument(skip(self, options))]
+    pub async fn block_tree(
+        &self,
+        block_id: &impl AsBlockId,
+        options: &query::BlockTree,
+    ) -> Result<Vec<data::Block>> {
+        let permits = Semaphore::new(options.concurrency.max(1));
+
+        let mut children = self.block_children_stream(block_id).collect_all().await?;
+        self.fill_children(&mut children, 1, options, &permits)
+            .await?;
+
+        Ok(children)
+    }
+
+    #[async_recursion]
+    async fn fill_children(
+        &self,
+        blocks: &mut [data::Block],
+        depth: usize,
+        options: &query::BlockTree,
+        permits: &Semaphore,
+    ) -> Result<()> {
+        if options
+            .max_depth
+            .is_some_and(|max_depth| depth >= max_depth)
+        {
+            return Ok(());
+        }
+
+        let fills = blocks
+            .iter_mut()
+            .filter(|block| match block.r#type.as_str() {
+                "child_page" => options.child_pages && block.has_children,
+                "child_database" => options.child_databases,
+                "synced_block" => options.synced_blocks && block.has_children,
+                _ => block.has_children,
             })
-            .try_collect()?;
+            .map(|block| async move {
+                let mut children = {
+                    let _permit = permits.acquire().await.expect("semaphore is never closed");
+                    if block.r#type == "child_database" {
+                        self.database_rows_as_blocks(&DatabaseId::from_valid(&block.id))
+                            .await?
+                    } else {
+                        self.block_children_stream(&block.id).collect_all().await?
+                    }
+                };
+
+                self.fill_children(&mut children, depth + 1, options, permits)
+                    .await?;
+                block.children = children;
+                Ok::<_, Error>(())
+            });
+
+        future::try_join_all(fills).await?;
+        Ok(())
+    }
+
+    /// A page and its content as Markdown. The content of child pages is fetched unless they
+    /// are exported as links.
+    ///
+    /// # Errors
+    /// - If any of the requests fails.
+    /// - If the page or any of its blocks cannot be decoded.
+    #[instrument(skip(self, options), fields(page_id = %page_id))]
+    pub async fn export_markdown(
+        &self,
+        page_id: &PageId,
+        options: &markdown::Options,
+    ) -> Result<markdown::Export> {
+        let page = self.get_page(page_id).await?;
+        let tree = query::BlockTree {
+            child_pages: options.child_pages != markdown::ChildPages::Link,
+            ..default()
+        };
+        let content = self.block_tree(page_id, &tree).await?;
+
+        Ok(markdown::page(&page, &content, options))
+    }
+
+    /// Every row of a database as the `child_page` block of the row, built from the pages the
+    /// query returns.
+    async fn database_rows_as_blocks(&self, database_id: &DatabaseId) -> Result<Vec<data::Block>> {
+        let query = default();
+        let rows = self
+            .query_database_stream(database_id, &query)
+            .collect_all()
+            .await?;
+
+        Ok(rows.into_iter().map(data::Block::child_page).collect())
+    }
+
+    /// Like [`Client::block_children`], but children that fail to decode are reported in
+    /// [`data::LenientBlocks::failed`] instead of failing the whole request.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    #[instrument(skip(self))]
+    pub async fn block_children_lenient(
+        &self,
+        block_id: &impl AsBlockId,
+        query: query::BlockChildren,
+    ) -> Result<data::LenientBlocks> {
+        let Chunk { results, .. } = self.block_children_raw(block_id, &query).await?;
+
+        let mut res = data::LenientBlocks::default();
+        for result in results {
+            match error::decode_block(result) {
+                Ok(block) => res.blocks.push(block),
+                Err(err) => {
+                    tracing::warn!("{err}");
+                    res.failed.push(err);
+                }
+            }
+        }
         Ok(res)
     }
 
+    async fn block_children_raw(
+        &self,
+        block_id: &impl AsBlockId,
+        query: &query::BlockChildren,
+    ) -> Result<Chunk<serde_json::Value>> {
+        self.send(
+            self.request(Method::GET, &f!("blocks/{block_id}/children"))
+                .query(query),
+        )
+        .await
+    }
+
+    /// Append blocks to the children of a block (or page), in order.
+    ///
+    /// The API accepts at most 100 children per block and two levels of nesting in a single
+    /// request, except for the columns of column lists and the rows of tables, which have to be
+    /// created along with them. Anything beyond that is appended to the new blocks in follow-up
+    /// requests, so the children can be nested as deep as needed. Returns the new first level
+    /// children.
+    ///
+    /// # Errors
+    /// - If any of the requests fails. Blocks that were sent before stay appended.
+    /// - If any of the new blocks cannot be decoded.
+    /// - If the children of a new column list or table changed before the blocks nested in
+    ///   them could be appended.
+    #[instrument(skip(self, append), fields(children = append.children.len()))]
+    pub async fn append_block_children(
+        &self,
+        block_id: &impl AsBlockId,
+        append: &query::AppendBlockChildren,
+    ) -> Result<Vec<data::Block>> {
+        self.append_nested(
+            &block_id.to_block_id(),
+            &append.children,
+            append.after.clone(),
+        )
+        .await
+    }
+
+    #[async_recursion]
+    async fn append_nested(
+        &self,
+        block_id: &BlockId,
+        children: &[data::BlockData],
+        mut after: Option<BlockId>,
+    ) -> Result<Vec<data::Block>> {
+        #[derive(serde::Serialize)]
+        struct Body<'a> {
+            children: &'a [data::BlockData],
+            #[serde(skip_serializing_if = "Option::is_none")]
+            after: Option<&'a BlockId>,
+        }
+
+        let mut res = Vec::with_capacity(children.len());
+
+        for chunk in children.chunks(MAX_CHILDREN) {
+            let sent: Vec<_> = chunk.iter().map(first_levels).collect();
+            let body = Body {
+                children: &sent,
+                after: after.as_ref(),
+            };
+
+            let Chunk { results, .. } = self
+                .send(
+                    self.request(Method::PATCH, &f!("blocks/{block_id}/children"))
+                        .json(&body),
+                )
+                .await?;
+
+            let blocks: Vec<_> = results.into_iter().map(error::decode_block).try_collect()?;
+
+            // without `after` every chunk goes to the end anyway
+            if let (Some(after), Some(last)) = (&mut after, blocks.last()) {
+                after.clone_from(&last.id);
+            }
+
+            for ((block, data), sent) in blocks.iter().zip(chunk).zip(&sent) {
+                self.append_rest(&block.id, data, sent).await?;
+            }
+
+            res.extend(blocks);
+        }
+
+        Ok(res)
+    }
+
+    /// Append the children of `data` that were left out of `sent`, which was created as
+    /// `block_id`.
+    #[async_recursion]
+    async fn append_rest(
+        &self,
+        block_id: &BlockId,
+        data: &data::BlockData,
+        sent: &data::BlockData,
+    ) -> Result<()> {
+        let (created, rest) = data.children().split_at(sent.children().len());
+
+        // Only columns and table rows are sent along with content of their own. Responses
+        // only contain the first level, so their ids are fetched.
+        if !created
+            .iter()
+            .zip(sent.children())
+            .all(|(data, sent)| is_complete(data, sent))
+        {
+            let blocks = self.block_children_stream(block_id).collect_all().await?;
+            let matches = blocks.len() == created.len()
+                && blocks.iter().zip(created).all(|(block, data)| {
+                    std::mem::discriminant(&block.data) == std::mem::discriminant(data)
+                });
+            if !matches {
+                return Err(Error::ChildrenChanged {
+                    block_id: block_id.clone(),
+                });
+            }
+
+            for ((block, data), sent) in blocks.iter().zip(created).zip(sent.children()) {
+                self.append_rest(&block.id, data, sent).await?;
+            }
+        }
+
+        if !rest.is_empty() {
+            self.append_nested(block_id, rest, None).await?;
+        }
+        Ok(())
+    }
+
+    /// Replace the content of a block. Children in `data` are ignored by Notion.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    #[instrument(skip(self, data), fields(block_id = %block_id))]
+    pub async fn update_block(
+        &self,
+        block_id: &impl AsBlockId,
+        data: &data::BlockData,
+    ) -> Result<data::Block> {
+        self.send(
+            self.request(Method::PATCH, &f!("blocks/{block_id}"))
+                .json(data),
+        )
+        .await
+    }
+
+    /// Move a block to the trash. Returns the archived block.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    #[instrument(skip(self), fields(block_id = %block_id))]
+    pub async fn delete_block(&self, block_id: &impl AsBlockId) -> Result<data::Block> {
+        self.send(self.request(Method::DELETE, &f!("blocks/{block_id}")))
+            .await
+    }
+
     /// # Errors
     /// - If the request fails.
     /// - If the response is not a page.
     #[instrument(skip(self), fields(page_id = %page_id))]
-    pub async fn get_page(&self, page_id: &str) -> Result<data::Page> {
+    pub async fn get_page(&self, page_id: &PageId) -> Result<data::Page> {
         let response: data::Object = self
-            .request(Method::GET, &f!("pages/{page_id}"))
-            .send()
-            .await?
-            .json()
+            .send(self.request(Method::GET, &f!("pages/{page_id}")))
             .await?;
 
-        let data::Object::Page(page) = response else {
-            bail!("Result {response:?} is not a page")
-        };
+        into_page(response)
+    }
 
+    /// Create a page under a page or a row in a database.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a page.
+    #[instrument(skip(self, page))]
+    pub async fn create_page(&self, page: &query::CreatePage) -> Result<data::Page> {
+        let response: data::Object = self
+            .send(self.request(Method::POST, "pages").json(page))
+            .await?;
+
+        into_page(response)
+    }
+
+    /// Update the properties, icon or cover of a page, or archive it.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a page.
+    #[instrument(skip(self, update), fields(page_id = %page_id))]
+    pub async fn update_page(
+        &self,
+        page_id: &PageId,
+        update: &query::UpdatePage,
+    ) -> Result<data::Page> {
+        let response: data::Object = self
+            .send(
+                self.request(Method::PATCH, &f!("pages/{page_id}"))
+                    .json(update),
+            )
+            .await?;
+
+        into_page(response)
+    }
+
+    /// Like [`Client::get_page`], with `options`.
+    ///
+    /// # Errors
+    /// - If any of the requests fails.
+    /// - If the response is not a page.
+    /// - If a hydrated property cannot be decoded.
+    #[instrument(skip(self), fields(page_id = %page_id))]
+    pub async fn get_page_with(
+        &self,
+        page_id: &PageId,
+        options: &query::GetPage,
+    ) -> Result<data::Page> {
+        let mut page = self.get_page(page_id).await?;
+        if !options.hydrate_properties {
+            return Ok(page);
+        }
+
+        let truncated: Vec<_> = page
+            .properties
+            .iter()
+            .filter(|(_, property)| property.may_be_truncated())
+            .map(|(name, property)| (name.clone(), property.id.clone()))
+            .collect();
+        let values = future::try_join_all(
+            truncated
+                .iter()
+                .map(|(_, property_id)| self.get_page_property(page_id, property_id)),
+        )
+        .await?;
+
+        for ((name, _), data) in truncated.into_iter().zip(values) {
+            if let Some(property) = page.properties.get_mut(&name) {
+                property.data = data;
+                property.has_more = false;
+            }
+        }
         Ok(page)
     }
 
+    /// The complete value of a page property, following pagination.
+    ///
+    /// Unlike in [`data::Page::properties`], titles, rich text, relations and people are not
+    /// limited to 25 items, and rollups are computed from every related page.
+    ///
+    /// # Errors
+    /// - If any of the requests fails.
+    /// - If the property cannot be decoded.
+    #[instrument(skip(self), fields(page_id = %page_id))]
+    pub async fn get_page_property(
+        &self,
+        page_id: &PageId,
+        property_id: &str,
+    ) -> Result<data::PropertyData> {
+        let url = f!("pages/{page_id}/properties/{property_id}");
+        let mut query = query::PropertyItems::default();
+        let mut items = Vec::new();
+
+        let value = loop {
+            let response = self
+                .send(self.request(Method::GET, &url).query(&query))
+                .await?;
+
+            match response {
+                PropertyItems::PropertyItem(item) => break join_property_items(item, None),
+                PropertyItems::List {
+                    results,
+                    next_cursor,
+                    has_more,
+                    property_item,
+                } => {
+                    items.extend(results);
+                    match next_cursor {
+                        Some(cursor) if has_more => query.start_cursor = Some(cursor),
+                        _ => break join_property_items(property_item, Some(items)),
+                    }
+                }
+            }
+        };
+
+        Ok(serde_path_to_error::deserialize(&value)?)
+    }
+
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a database.
+    #[instrument(skip(self), fields(database_id = %database_id))]
+    pub async fn get_database(&self, database_id: &DatabaseId) -> Result<data::Database> {
+        let response: data::Object = self
+            .send(self.request(Method::GET, &f!("databases/{database_id}")))
+            .await?;
+
+        into_database(response)
+    }
+
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a database.
+    #[instrument(skip(self, database))]
+    pub async fn create_database(
+        &self,
+        database: &query::CreateDatabase,
+    ) -> Result<data::Database> {
+        let response: data::Object = self
+            .send(self.request(Method::POST, "databases").json(database))
+            .await?;
+
+        into_database(response)
+    }
+
+    /// Update the title, description or properties of a database.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a database.
+    #[instrument(skip(self, update), fields(database_id = %database_id))]
+    pub async fn update_database(
+        &self,
+        database_id: &DatabaseId,
+        update: &query::UpdateDatabase,
+    ) -> Result<data::Database> {
+        let response: data::Object = self
+            .send(
+                self.request(Method::PATCH, &f!("databases/{database_id}"))
+                    .json(update),
+            )
+            .await?;
+
+        into_database(response)
+    }
+
+    /// Only returns the page of rows selected by `query`. Use [`Client::query_database_stream`]
+    /// to get all of them.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a list of pages.
+    #[instrument(skip(self, query), fields(database_id = %database_id))]
+    pub async fn query_database(
+        &self,
+        database_id: &DatabaseId,
+        query: &query::DatabaseQuery,
+    ) -> Result<Vec<data::Page>> {
+        let Chunk { results, .. } = self.query_database_raw(database_id, query).await?;
+        Ok(results)
+    }
+
+    /// All rows of a database matching `query`, following pagination lazily.
+    ///
+    /// `query.start_cursor` is ignored. Each item fails if the request for its page fails.
+    pub fn query_database_stream<'a>(
+        &'a self,
+        database_id: &'a DatabaseId,
+        query: &'a query::DatabaseQuery,
+    ) -> impl Stream<Item = Result<data::Page>> + 'a {
+        paginate(move |start_cursor| {
+            let query = query::DatabaseQuery {
+                start_cursor,
+                ..query.clone()
+            };
+            async move { self.query_database_raw(database_id, &query).await }
+        })
+    }
+
+    async fn query_database_raw(
+        &self,
+        database_id: &DatabaseId,
+        query: &query::DatabaseQuery,
+    ) -> Result<Chunk<data::Page>> {
+        self.send(
+            self.request(Method::POST, &f!("databases/{database_id}/query"))
+                .json(query),
+        )
+        .await
+    }
+
+    /// Only returns the first page of results. Use [`Client::search_stream`] to get all of them.
+    ///
+    /// An empty `query` returns every page and database shared with the integration.
+    ///
     /// # Errors
     /// - If the request fails.
     #[instrument(skip(self), fields(query = %query))]
     pub async fn search(&self, query: &str) -> Result<SearchResponse> {
-        use data::SearchRequest;
-
         let query = match query {
             "" => None,
             _ => Some(query),
         };
 
-        let req = SearchRequest { query, ..default() };
+        self.search_with(&data::SearchRequest { query, ..default() })
+            .await
+    }
+
+    /// Search with a filter, sort or cursor. Only returns the page of results selected by
+    /// `request`.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    #[instrument(skip(self))]
+    pub async fn search_with(&self, request: &data::SearchRequest<'_>) -> Result<SearchResponse> {
+        self.send(self.request(Method::POST, "search").json(request))
+            .await
+    }
+
+    /// All results of a search, following pagination lazily.
+    ///
+    /// `request.start_cursor` is ignored. Each item fails if the request for its page fails.
+    pub fn search_stream<'a>(
+        &'a self,
+        request: data::SearchRequest<'a>,
+    ) -> impl Stream<Item = Result<data::PageOrDatabase>> + 'a {
+        paginate(move |start_cursor| {
+            let request = request.clone();
+            async move {
+                let req = data::SearchRequest {
+                    start_cursor: start_cursor.as_deref(),
+                    ..request
+                };
+                let SearchResponse {
+                    results,
+                    next_cursor,
+                    has_more,
+                    ..
+                } = self
+                    .send(self.request(Method::POST, "search").json(&req))
+                    .await?;
+
+                Ok(Chunk {
+                    results,
+                    next_cursor,
+                    has_more,
+                })
+            }
+        })
+    }
+
+    /// Only returns the page of comments selected by `query`. Use
+    /// [`Client::list_comments_stream`] to get all of them.
+    ///
+    /// Lists the open comments on a block, or the page-level comments if `block_id` is a page.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    #[instrument(skip(self))]
+    pub async fn list_comments(
+        &self,
+        block_id: &impl AsBlockId,
+        query: query::ListComments,
+    ) -> Result<Vec<data::Comment>> {
+        let Chunk { results, .. } = self.list_comments_raw(block_id, &query).await?;
+        Ok(results)
+    }
+
+    /// All open comments on a block or page, following pagination lazily.
+    ///
+    /// Each item fails if the request for its page fails.
+    pub fn list_comments_stream<'a>(
+        &'a self,
+        block_id: &'a impl AsBlockId,
+    ) -> impl Stream<Item = Result<data::Comment>> + 'a {
+        paginate(move |start_cursor| {
+            let query = query::ListComments {
+                start_cursor,
+                ..default()
+            };
+            async move { self.list_comments_raw(block_id, &query).await }
+        })
+    }
+
+    async fn list_comments_raw(
+        &self,
+        block_id: &impl AsBlockId,
+        query: &query::ListComments,
+    ) -> Result<Chunk<data::Comment>> {
+        self.send(
+            self.request(Method::GET, "comments")
+                .query(&[("block_id", block_id.to_block_id())])
+                .query(query),
+        )
+        .await
+    }
 
-        let response = self
-            .request(Method::POST, "search")
-            .json(&req)
-            .send()
-            .await?
-            .json()
+    /// Start a discussion on a page or reply to an existing one.
+    ///
+    /// # Errors
+    /// - If the request fails.
+    /// - If the response is not a comment.
+    #[instrument(skip(self, comment))]
+    pub async fn create_comment(&self, comment: &query::CreateComment) -> Result<data::Comment> {
+        let response: data::Object = self
+            .send(self.request(Method::POST, "comments").json(comment))
             .await?;
 
-        Ok(response)
+        let data::Object::Comment(comment) = response else {
+            return Err(unexpected("comment", &response));
+        };
+        Ok(*comment)
+    }
+}
+
+fn into_user(response: data::Object) -> Result<data::User> {
+    let data::Object::User(user) = response else {
+        return Err(unexpected("user", &response));
+    };
+    Ok(user)
+}
+
+fn into_page(response: data::Object) -> Result<data::Page> {
+    let data::Object::Page(page) = response else {
+        return Err(unexpected("page", &response));
+    };
+    Ok(*page)
+}
+
+fn into_database(response: data::Object) -> Result<data::Database> {
+    let data::Object::Database(database) = response else {
+        return Err(unexpected("database", &response));
+    };
+    Ok(*database)
+}
+
+fn unexpected(expected: &'static str, found: &data::Object) -> Error {
+    Error::UnexpectedObject {
+        expected,
+        found: found.kind(),
     }
 }
 
+/// The most children of a block the API accepts in one request.
+const MAX_CHILDREN: usize = 100;
+
+/// `block` as far as the API accepts it in one request: with at most [`MAX_CHILDREN`]
+/// children, which only keep the children they have to be created with.
+///
+/// If that would leave out content of the children, the children are appended in a follow-up
+/// request instead, which returns their ids.
+fn first_levels(block: &data::BlockData) -> data::BlockData {
+    let mut res = block.clone();
+    if let Some(children) = res.children_mut() {
+        children.truncate(MAX_CHILDREN);
+        for (child, data) in children.iter_mut().zip(block.children()) {
+            *child = inline(data);
+        }
+
+        let complete = children
+            .iter()
+            .zip(block.children())
+            .all(|(sent, data)| is_complete(data, sent));
+        if !complete && !needs_children(block) {
+            children.clear();
+        }
+    }
+    res
+}
+
+/// `block` without children, unless it has to be created with them.
+fn inline(block: &data::BlockData) -> data::BlockData {
+    let mut res = block.clone();
+    if let Some(children) = res.children_mut() {
+        if needs_children(block) {
+            children.truncate(MAX_CHILDREN);
+            for (child, data) in children.iter_mut().zip(block.children()) {
+                *child = inline(data);
+            }
+        } else {
+            children.clear();
+        }
+    }
+    res
+}
+
+/// Column lists, columns and tables cannot be created empty.
+const fn needs_children(block: &data::BlockData) -> bool {
+    matches!(
+        block,
+        data::BlockData::ColumnList { .. }
+            | data::BlockData::Column { .. }
+            | data::BlockData::Table { .. }
+    )
+}
+
+/// Whether `sent` contains all nested children of `data`.
+fn is_complete(data: &data::BlockData, sent: &data::BlockData) -> bool {
+    data.children().len() == sent.children().len()
+        && data
+            .children()
+            .iter()
+            .zip(sent.children())
+            .all(|(data, sent)| is_complete(data, sent))
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use once_cell::sync::Lazy;
+    use wiremock::{
+        matchers::{body_json, method, path, query_param},
+        Mock, MockServer, Request, Respond, ResponseTemplate,
+    };
 
-    use crate::{data::BlockData, default, Client};
+    use crate::{
+        data::{self, BlockData, Color, PropertyData, RichText, RollupValue, User},
+        default, markdown,
+        query::{AppendBlockChildren, BlockTree, CreateComment, GetPage},
+        BlockId, Client, CollectAll, Error, PageId,
+    };
 
     static API: Lazy<Client> =
         Lazy::new(|| Client::new(std::env::var("NOTION_ACCESS_TOKEN").unwrap()));
 
-    static MEETING_PAGE_ID: Lazy<Box<str>> = Lazy::new(|| {
+    static MEETING_PAGE_ID: Lazy<PageId> = Lazy::new(|| {
         let s = std::env::var("NOTION_MEETING_PAGE_ID").unwrap();
-        Box::from(s)
+        s.parse().unwrap()
     });
 
     #[tokio::test]
@@ -197,7 +989,7 @@ mod tests {
     #[tokio::test]
     async fn test_block_children() {
         let res = API
-            .block_children(&MEETING_PAGE_ID, default())
+            .block_children(&*MEETING_PAGE_ID, default())
             .await
             .unwrap();
 
@@ -211,9 +1003,55 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_block_children_stream() {
+        let server = MockServer::start().await;
+        let parent = id(0);
+
+        Mock::given(method("GET"))
+            .and(path(format!("/blocks/{parent}/children")))
+            .and(query_param("start_cursor", "next"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(list_json(vec![block_json(
+                    &id(3),
+                    "paragraph",
+                    false,
+                )])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/blocks/{parent}/children")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "results": [
+                    block_json(&id(1), "paragraph", false),
+                    block_json(&id(2), "divider", false)
+                ],
+                "next_cursor": "next",
+                "has_more": true
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let blocks = client
+            .block_children_stream(&parent.parse::<BlockId>().unwrap())
+            .collect_all()
+            .await
+            .unwrap();
+        let ids: Vec<_> = blocks.iter().map(|block| block.id.to_string()).collect();
+        assert_eq!(ids, [id(1), id(2), id(3)]);
+    }
+
     #[tokio::test]
     async fn test_retrieve_block() {
-        let res = API.block(&MEETING_PAGE_ID).await.unwrap();
+        let res = API.block(&*MEETING_PAGE_ID).await.unwrap();
         println!("{res:#?}");
     }
 
@@ -221,4 +1059,750 @@ mod tests {
     async fn test_search() {
         API.search("").await.unwrap();
     }
+
+    /// A valid id that is easy to tell apart in assertions.
+    fn id(n: u32) -> String {
+        format!("00000000-0000-4000-8000-{n:012}")
+    }
+
+    fn block_json(id: &str, ty: &str, has_children: bool) -> serde_json::Value {
+        serde_json::json!({
+            "object": "block",
+            "id": id,
+            "type": ty,
+            ty: { "rich_text": [], "color": "default", "title": "Sub page" },
+            "created_time": "2023-03-13T03:32:00.000Z",
+            "created_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "last_edited_time": "2023-03-13T03:32:00.000Z",
+            "last_edited_by": { "object": "user", "id": "ee5f0f84-409a-440f-983a-a5315961c6e4" },
+            "archived": false,
+            "has_children": has_children
+        })
+    }
+
+    fn list_json(results: Vec<serde_json::Value>) -> serde_json::Value {
+        serde_json::json!({
+            "object": "list",
+            "results": results,
+            "next_cursor": null,
+            "has_more": false
+        })
+    }
+
+    /// Responds to an append with one paragraph per child, with ids continuing from the
+    /// number of blocks created so far.
+    struct AppendResponder(AtomicUsize);
+
+    impl Respond for AppendResponder {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: serde_json::Value = request.body_json().unwrap();
+            let count = body["children"].as_array().unwrap().len();
+            let start = self.0.fetch_add(count, Ordering::SeqCst);
+
+            let results: Vec<_> = (start..start + count)
+                .map(|n| block_json(&id(1000 + n as u32), "paragraph", false))
+                .collect();
+
+            ResponseTemplate::new(200).set_body_json(list_json(results))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_block_children_in_chunks() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("PATCH"))
+            .and(path(format!("/blocks/{}/children", id(0))))
+            .respond_with(AppendResponder(AtomicUsize::new(0)))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let children = (0..250)
+            .map(|i| BlockData::paragraph(format!("line {i}")))
+            .collect();
+        let start: BlockId = id(1).parse().unwrap();
+        let append = AppendBlockChildren::new(children).after(start);
+
+        let blocks = client
+            .append_block_children(&id(0).parse::<PageId>().unwrap(), &append)
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 250);
+
+        let requests = server.received_requests().await.unwrap();
+        let bodies: Vec<serde_json::Value> = requests
+            .iter()
+            .map(|request| request.body_json().unwrap())
+            .collect();
+
+        let sizes: Vec<_> = bodies
+            .iter()
+            .map(|body| body["children"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(sizes, [100, 100, 50]);
+
+        let afters: Vec<_> = bodies.iter().map(|body| body["after"].clone()).collect();
+        assert_eq!(afters, [id(1), id(1099), id(1199)]);
+
+        let first = &bodies[0]["children"][0]["paragraph"]["rich_text"][0]["plain_text"];
+        assert_eq!(first, "line 0");
+    }
+
+    async fn mount_children(server: &MockServer, id: &str, children: Vec<serde_json::Value>) {
+        Mock::given(method("GET"))
+            .and(path(format!("/blocks/{id}/children")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_json(children)))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_block_tree() {
+        let server = MockServer::start().await;
+
+        let (root, a, b, page, a1, a1x) = (id(0), id(1), id(2), id(3), id(11), id(111));
+        mount_children(
+            &server,
+            &root,
+            vec![
+                block_json(&a, "paragraph", true),
+                block_json(&b, "paragraph", false),
+                block_json(&page, "child_page", true),
+            ],
+        )
+        .await;
+        mount_children(&server, &a, vec![block_json(&a1, "paragraph", true)]).await;
+        mount_children(&server, &a1, vec![block_json(&a1x, "paragraph", false)]).await;
+        let root: BlockId = root.parse().unwrap();
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .rate_limit(None)
+            .build()
+            .unwrap();
+
+        let tree = client.block_tree(&root, &default()).await.unwrap();
+        let ids: Vec<_> = tree.iter().map(|block| block.id.to_string()).collect();
+        assert_eq!(ids, [a, b, page]);
+        assert_eq!(tree[0].children[0].id, a1.as_str());
+        assert_eq!(tree[0].children[0].children[0].id, a1x.as_str());
+        assert!(tree[1].children.is_empty());
+        // child pages are skipped by default
+        assert!(tree[2].children.is_empty());
+
+        let options = BlockTree {
+            max_depth: Some(2),
+            ..default()
+        };
+        let tree = client.block_tree(&root, &options).await.unwrap();
+        assert_eq!(tree[0].children[0].id, a1.as_str());
+        assert!(tree[0].children[0].children.is_empty());
+        for max_depth in [0, 1] {
+            let options = BlockTree {
+                max_depth: Some(max_depth),
+                ..default()
+            };
+            let tree = client.block_tree(&root, &options).await.unwrap();
+            assert_eq!(tree.len(), 3);
+            assert!(tree[0].children.is_empty());
+        }
+    }
+
+    fn nested(text: &str, children: Vec<BlockData>) -> BlockData {
+        BlockData::Paragraph {
+            rich_text: vec![RichText::plain(text)],
+            color: Color::Default,
+            children,
+        }
+    }
+
+    /// The path and body of every append the server received.
+    async fn appends(server: &MockServer) -> Vec<(String, serde_json::Value)> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.method.to_string() == "PATCH")
+            .map(|request| (request.url.path().to_string(), request.body_json().unwrap()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_append_block_children_nested() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .respond_with(AppendResponder(AtomicUsize::new(0)))
+            .mount(&server)
+            .await;
+
+        let grandchild = nested("grandchild", vec![BlockData::paragraph("great-grandchild")]);
+        let deep = nested("parent", vec![nested("child", vec![grandchild])]);
+        let items = (0..120)
+            .map(|i| BlockData::paragraph(format!("item {i}")))
+            .collect();
+        let append = AppendBlockChildren::new(vec![deep, nested("list", items)]);
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let blocks = client
+            .append_block_children(&id(0).parse::<PageId>().unwrap(), &append)
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 2);
+
+        // Each level is appended to the id returned for its parent, no children are listed.
+        let appends = appends(&server).await;
+        let paths: Vec<_> = appends.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [id(0), id(1000), id(1002), id(1001)].map(|id| format!("/blocks/{id}/children"))
+        );
+        assert!(server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .all(|request| request.method.to_string() == "PATCH"));
+
+        let first = &appends[0].1["children"];
+        assert!(first[0]["paragraph"].get("children").is_none());
+        let list = first[1]["paragraph"]["children"].as_array().unwrap();
+        assert_eq!(list.len(), 100);
+
+        let child = &appends[1].1["children"][0]["paragraph"];
+        assert_eq!(child["rich_text"][0]["plain_text"], "child");
+        assert!(child.get("children").is_none());
+
+        let grandchild = &appends[2].1["children"][0]["paragraph"];
+        assert_eq!(grandchild["rich_text"][0]["plain_text"], "grandchild");
+        assert_eq!(grandchild["children"].as_array().unwrap().len(), 1);
+
+        let rest = appends[3].1["children"].as_array().unwrap();
+        assert_eq!(rest.len(), 20);
+        assert_eq!(
+            rest[0]["paragraph"]["rich_text"][0]["plain_text"],
+            "item 100"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_column_list() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .respond_with(AppendResponder(AtomicUsize::new(0)))
+            .mount(&server)
+            .await;
+        // the columns and their content, created along with the column list
+        let (left, right, content) = (id(501), id(502), id(601));
+        mount_children(
+            &server,
+            &id(1000),
+            vec![
+                block_json(&left, "column", true),
+                block_json(&right, "column", true),
+            ],
+        )
+        .await;
+        mount_children(
+            &server,
+            &left,
+            vec![block_json(&content, "paragraph", true)],
+        )
+        .await;
+
+        let columns = BlockData::ColumnList {
+            children: vec![
+                BlockData::Column {
+                    children: vec![nested("left", vec![BlockData::paragraph("nested")])],
+                },
+                BlockData::Column {
+                    children: vec![BlockData::paragraph("right")],
+                },
+            ],
+        };
+        let append = AppendBlockChildren::new(vec![columns]);
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        client
+            .append_block_children(&id(0).parse::<PageId>().unwrap(), &append)
+            .await
+            .unwrap();
+
+        let appends = appends(&server).await;
+        let paths: Vec<_> = appends.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [id(0), content].map(|id| format!("/blocks/{id}/children"))
+        );
+
+        let columns = &appends[0].1["children"][0]["column_list"]["children"];
+        let left = &columns[0]["column"]["children"][0]["paragraph"];
+        assert_eq!(left["rich_text"][0]["plain_text"], "left");
+        assert!(left.get("children").is_none());
+        let right = &columns[1]["column"]["children"][0]["paragraph"];
+        assert_eq!(right["rich_text"][0]["plain_text"], "right");
+
+        let nested = &appends[1].1["children"][0]["paragraph"];
+        assert_eq!(nested["rich_text"][0]["plain_text"], "nested");
+    }
+
+    #[tokio::test]
+    async fn test_append_fails_if_children_changed() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .respond_with(AppendResponder(AtomicUsize::new(0)))
+            .mount(&server)
+            .await;
+        // someone deleted a column in the meantime
+        mount_children(
+            &server,
+            &id(1000),
+            vec![block_json(&id(501), "column", true)],
+        )
+        .await;
+
+        let column = |text: &str| BlockData::Column {
+            children: vec![nested(text, vec![BlockData::paragraph("nested")])],
+        };
+        let columns = BlockData::ColumnList {
+            children: vec![column("left"), column("right")],
+        };
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let append = AppendBlockChildren::new(vec![columns]);
+        let err = client
+            .append_block_children(&id(0).parse::<PageId>().unwrap(), &append)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ChildrenChanged { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_block_tree_with_child_databases() {
+        let server = MockServer::start().await;
+
+        let (root, database) = (id(0), id(1));
+        mount_children(
+            &server,
+            &root,
+            vec![block_json(&database, "child_database", false)],
+        )
+        .await;
+        let row: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/page.json")).unwrap();
+        Mock::given(method("POST"))
+            .and(path(format!("/databases/{database}/query")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_json(vec![row])))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let options = BlockTree {
+            child_databases: true,
+            ..default()
+        };
+        let tree = client
+            .block_tree(&root.parse::<BlockId>().unwrap(), &options)
+            .await
+            .unwrap();
+
+        let rows = &tree[0].children;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, "59833787-2cf9-4fdf-8782-e53db20768a5");
+        assert!(
+            matches!(&rows[0].data, BlockData::ChildPage { title } if title == "Tuscan kale"),
+            "{:?}",
+            rows[0].data
+        );
+        // the rows come from the query, not from a request per row
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_user_and_me() {
+        let server = MockServer::start().await;
+
+        let user_id = id(7);
+        Mock::given(method("GET"))
+            .and(path(format!("/users/{user_id}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "user",
+                "id": user_id,
+                "name": "Ada",
+                "avatar_url": null,
+                "type": "person",
+                "person": { "email": "ada@example.com" }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/users/me"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "user",
+                "id": id(8),
+                "name": "Pantry sync",
+                "avatar_url": null,
+                "type": "bot",
+                "bot": {
+                    "owner": { "type": "workspace", "workspace": true },
+                    "workspace_name": "Kitchen"
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let user = client.get_user(&user_id.parse().unwrap()).await.unwrap();
+        let User::Person { person, .. } = &user else {
+            panic!("expected person, got {user:?}");
+        };
+        assert_eq!(person.email.as_deref(), Some("ada@example.com"));
+
+        let me = client.me().await.unwrap();
+        let User::Bot { bot, .. } = &me else {
+            panic!("expected bot, got {me:?}");
+        };
+        assert_eq!(bot.workspace_name.as_deref(), Some("Kitchen"));
+    }
+
+    fn comment_json(n: u32, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "object": "comment",
+            "id": id(n),
+            "parent": { "type": "page_id", "page_id": id(0) },
+            "discussion_id": id(100),
+            "created_time": "2023-03-13T03:32:00.000Z",
+            "last_edited_time": "2023-03-13T03:32:00.000Z",
+            "created_by": { "object": "user", "id": id(7) },
+            "rich_text": [RichText::plain(text)]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_comments() {
+        let server = MockServer::start().await;
+        let page = id(0);
+
+        Mock::given(method("GET"))
+            .and(path("/comments"))
+            .and(query_param("block_id", page.as_str()))
+            .and(query_param("start_cursor", "next"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(list_json(vec![comment_json(2, "Agreed")])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/comments"))
+            .and(query_param("block_id", page.as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "results": [comment_json(1, "Needs a source")],
+                "next_cursor": "next",
+                "has_more": true
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/comments"))
+            .and(body_json(serde_json::json!({
+                "discussion_id": id(100),
+                "rich_text": [RichText::plain("Fixed")]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(comment_json(3, "Fixed")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let block_id: BlockId = page.parse().unwrap();
+        let comments = client
+            .list_comments_stream(&block_id)
+            .collect_all()
+            .await
+            .unwrap();
+        let texts: Vec<_> = comments.iter().map(data::Comment::plain_text).collect();
+        assert_eq!(texts, ["Needs a source", "Agreed"]);
+
+        let reply = CreateComment::reply(comments[0].discussion_id.clone(), "Fixed");
+        let comment = client.create_comment(&reply).await.unwrap();
+        assert_eq!(comment.plain_text(), "Fixed");
+        assert_eq!(comment.discussion_id, comments[0].discussion_id);
+    }
+
+    async fn mount_property(
+        server: &MockServer,
+        property_id: &str,
+        cursor: Option<&str>,
+        body: serde_json::Value,
+    ) {
+        let page_id = "59833787-2cf9-4fdf-8782-e53db20768a5";
+        let mut mock = Mock::given(method("GET"))
+            .and(path(format!("/pages/{page_id}/properties/{property_id}")));
+        if let Some(cursor) = cursor {
+            mock = mock.and(query_param("start_cursor", cursor));
+        }
+        mock.respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    fn property_items(
+        results: Vec<serde_json::Value>,
+        next_cursor: Option<&str>,
+        property_item: serde_json::Value,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "object": "list",
+            "results": results,
+            "next_cursor": next_cursor,
+            "has_more": next_cursor.is_some(),
+            "type": "property_item",
+            "property_item": property_item
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_page_hydrates_properties() {
+        let server = MockServer::start().await;
+        let page: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/page.json")).unwrap();
+        Mock::given(method("GET"))
+            .and(path("/pages/59833787-2cf9-4fdf-8782-e53db20768a5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .mount(&server)
+            .await;
+
+        let relation = |n| {
+            serde_json::json!({
+                "object": "property_item",
+                "id": "YfIu",
+                "type": "relation",
+                "relation": { "id": id(n) }
+            })
+        };
+        let item = serde_json::json!({ "id": "YfIu", "type": "relation", "relation": {} });
+        mount_property(
+            &server,
+            "YfIu",
+            Some("second"),
+            property_items(vec![relation(2), relation(3)], None, item.clone()),
+        )
+        .await;
+        mount_property(
+            &server,
+            "YfIu",
+            None,
+            property_items(vec![relation(1)], Some("second"), item),
+        )
+        .await;
+
+        let count = serde_json::json!({
+            "id": "Z%5CEh",
+            "type": "rollup",
+            "rollup": { "type": "number", "number": 30, "function": "count" }
+        });
+        mount_property(&server, "Z%5CEh", None, property_items(vec![], None, count)).await;
+
+        let number = |n| serde_json::json!({ "object": "property_item", "id": "MnA%3F", "type": "number", "number": n });
+        let original = serde_json::json!({
+            "id": "MnA%3F",
+            "type": "rollup",
+            "rollup": { "type": "array", "array": {}, "function": "show_original" }
+        });
+        mount_property(
+            &server,
+            "MnA%3F",
+            None,
+            property_items(vec![number(1), number(2), number(3)], None, original),
+        )
+        .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+        let page_id: PageId = "59833787-2cf9-4fdf-8782-e53db20768a5".parse().unwrap();
+
+        let relation = client.get_page_property(&page_id, "YfIu").await.unwrap();
+        assert_eq!(relation.as_relation().unwrap().len(), 3);
+
+        let options = GetPage {
+            hydrate_properties: true,
+        };
+        let page = client.get_page_with(&page_id, &options).await.unwrap();
+        let recipes = &page.properties["Recipes"];
+        assert!(!recipes.has_more);
+        let ids: Vec<_> = recipes
+            .data
+            .as_relation()
+            .unwrap()
+            .iter()
+            .map(|page| page.id.to_string())
+            .collect();
+        assert_eq!(ids, [id(1), id(2), id(3)]);
+
+        let count = page
+            .property("Number of meals")
+            .unwrap()
+            .as_rollup()
+            .unwrap();
+        assert!(matches!(count.value, RollupValue::Number { number: Some(n) } if n == 30.0));
+
+        let original = page.property("Meal names").unwrap().as_rollup().unwrap();
+        let RollupValue::Array { array } = &original.value else {
+            panic!("expected rollup array");
+        };
+        let numbers: Vec<_> = array.iter().filter_map(PropertyData::as_number).collect();
+        assert_eq!(numbers, [1.0, 2.0, 3.0]);
+
+        // not truncated, so not fetched again
+        assert_eq!(page.property("Price").unwrap().as_number(), Some(2.5));
+    }
+
+    #[tokio::test]
+    async fn test_search_with() {
+        let server = MockServer::start().await;
+
+        let page: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/page.json")).unwrap();
+        let database = serde_json::json!({
+            "object": "database",
+            "id": id(5),
+            "created_time": "2023-03-13T03:32:00.000Z",
+            "last_edited_time": "2023-03-13T03:32:00.000Z",
+            "created_by": { "object": "user", "id": id(7) },
+            "last_edited_by": { "object": "user", "id": id(7) },
+            "title": [RichText::plain("Groceries")],
+            "icon": null,
+            "cover": null,
+            "properties": {},
+            "parent": { "type": "workspace", "workspace": true },
+            "url": "https://www.notion.so/groceries",
+            "archived": false
+        });
+
+        Mock::given(method("POST"))
+            .and(path("/search"))
+            .and(body_json(serde_json::json!({
+                "query": "kale",
+                "sort": { "direction": "descending", "timestamp": "last_edited_time" },
+                "start_cursor": "next",
+                "page_size": 10
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "results": [page, database],
+                "next_cursor": null,
+                "has_more": false,
+                "type": "page_or_database",
+                "page_or_database": {}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let request = data::SearchRequest {
+            query: Some("kale"),
+            sort: Some(data::Sort::last_edited_time(
+                data::SortDirection::Descending,
+            )),
+            start_cursor: Some("next"),
+            page_size: Some(10),
+            ..default()
+        };
+        let response = client.search_with(&request).await.unwrap();
+        let titles: Vec<_> = response
+            .results
+            .iter()
+            .map(data::PageOrDatabase::title)
+            .collect();
+        assert_eq!(titles, ["Tuscan kale", "Groceries"]);
+        assert!(response.results[0].as_page().is_some());
+        assert!(response.results[1].as_database().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_export_markdown() {
+        let server = MockServer::start().await;
+
+        let page: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/page.json")).unwrap();
+        let page_id = page["id"].as_str().unwrap().to_string();
+        Mock::given(method("GET"))
+            .and(path(format!("/pages/{page_id}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .mount(&server)
+            .await;
+
+        let sub_page = id(2);
+        mount_children(
+            &server,
+            &page_id,
+            vec![block_json(&sub_page, "child_page", true)],
+        )
+        .await;
+        let mut paragraph = block_json(&id(3), "paragraph", false);
+        paragraph["paragraph"]["rich_text"] = serde_json::json!([RichText::plain("Stems out")]);
+        mount_children(&server, &sub_page, vec![paragraph]).await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let options = markdown::Options {
+            child_pages: markdown::ChildPages::Files,
+        };
+        let export = client
+            .export_markdown(&page_id.parse().unwrap(), &options)
+            .await
+            .unwrap();
+
+        let name = "Sub page 00000000000040008000000000000002.md";
+        assert_eq!(
+            export.markdown,
+            format!("# Tuscan kale\n\n[Sub page](<{name}>)")
+        );
+        assert_eq!(
+            export.files,
+            [markdown::File {
+                name: name.to_string(),
+                markdown: "# Sub page\n\nStems out".to_string(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,846 @@
+//! Conversion between blocks and [GitHub flavored Markdown](https://github.github.com/gfm/).
+//!
+//! For export, fetch the blocks with [`Client::block_tree`](crate::Client::block_tree) so
+//! nested content is included, or export a whole page with
+//! [`Client::export_markdown`](crate::Client::export_markdown). Blocks without a Markdown
+//! equivalent, like the table of contents, are left out. Colors and underlines are dropped.
+//!
+//! For import, [`parse`] turns Markdown into blocks to append.
+use crate::data::{
+    Annotations, Block, BlockData, FileObject, Heading, Icon, LinkToPage, Media, Page, RichText,
+};
+
+mod import;
+
+pub use import::parse;
+
+/// How to export the content of `child_page` blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChildPages {
+    /// A link to the page in Notion.
+    #[default]
+    Link,
+    /// The content in place, under a heading with the title.
+    Inline,
+    /// The content in a separate file in [`Export::files`], linked by its relative path.
+    Files,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub child_pages: ChildPages,
+}
+
+/// The result of an export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Export {
+    pub markdown: String,
+    /// Child pages exported with [`ChildPages::Files`], parents before their own child pages.
+    pub files: Vec<File>,
+}
+
+/// A child page exported to its own file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// `<title> <id>.md`, without characters that are not allowed in file names.
+    pub name: String,
+    pub markdown: String,
+}
+
+/// `blocks` and their children as Markdown.
+///
+/// Child pages whose content was not fetched are always exported as links.
+#[must_use]
+pub fn blocks(blocks: &[Block], options: &Options) -> Export {
+    let mut exporter = Exporter {
+        options,
+        files: Vec::new(),
+    };
+    let markdown = exporter.blocks(blocks);
+    Export {
+        markdown,
+        files: exporter.files,
+    }
+}
+
+/// `page` as Markdown, with its title as the top level heading followed by `blocks`, its
+/// content.
+#[must_use]
+pub fn page(page: &Page, content: &[Block], options: &Options) -> Export {
+    let mut export = blocks(content, options);
+    export.markdown = join([heading(1, &escape(&page.title())), export.markdown]);
+    export
+}
+
+/// Renders `text` with annotations and links.
+#[must_use]
+pub fn rich_text(text: &[RichText]) -> String {
+    text.iter().map(RichText::to_markdown).collect()
+}
+
+/// Escapes characters that would otherwise be read as inline Markdown.
+pub(crate) fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '~' | '<') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// `text` as a code span, with a fence longer than any run of backticks inside.
+pub(crate) fn code_span(text: &str) -> String {
+    let fence = "`".repeat(longest_run(text, '`') + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|other| other != c)
+        .map(str::len)
+        .max()
+        .unwrap_or(0)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum List {
+    Bulleted,
+    Numbered,
+    ToDo,
+}
+
+impl List {
+    const fn of(data: &BlockData) -> Option<Self> {
+        match data {
+            BlockData::BulletedListItem { .. } => Some(Self::Bulleted),
+            BlockData::NumberedListItem { .. } => Some(Self::Numbered),
+            BlockData::ToDo { .. } => Some(Self::ToDo),
+            _ => None,
+        }
+    }
+}
+
+struct Exporter<'a> {
+    options: &'a Options,
+    files: Vec<File>,
+}
+
+impl Exporter<'_> {
+    fn blocks(&mut self, blocks: &[Block]) -> String {
+        let mut res = String::new();
+        let mut previous = None;
+        let mut number = 0;
+        let mut bullet = '-';
+        for block in blocks {
+            let list = List::of(&block.data);
+            number = match list {
+                Some(List::Numbered) if previous == list => number + 1,
+                _ => 1,
+            };
+            // Bulleted and to-do lists use the same marker, so one directly after the other
+            // switches to the other marker to start a new list.
+            let bullets = [Some(List::Bulleted), Some(List::ToDo)];
+            bullet = match bullet {
+                _ if previous == list => bullet,
+                '-' if bullets.contains(&list) && bullets.contains(&previous) => '*',
+                _ => '-',
+            };
+
+            let markdown = self.block(block, number, bullet);
+            if markdown.is_empty() {
+                continue;
+            }
+            if !res.is_empty() {
+                // Items of the same list are kept together so the list stays tight.
+                res.push_str(if list.is_some() && list == previous {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            res.push_str(&markdown);
+            previous = list;
+        }
+        res
+    }
+
+    fn block(&mut self, block: &Block, number: usize, bullet: char) -> String {
+        use BlockData as B;
+
+        let children = &block.children;
+        match &block.data {
+            B::Paragraph { rich_text, .. } | B::Template { rich_text, .. } => {
+                let text = paragraph(rich_text);
+                join([text, self.blocks(children)])
+            }
+            B::Heading1(h) => self.heading(1, h, children),
+            B::Heading2(h) => self.heading(2, h, children),
+            B::Heading3(h) => self.heading(3, h, children),
+            B::BulletedListItem { rich_text, .. } => {
+                self.list_item(&format!("{bullet} "), 2, rich_text, children)
+            }
+            B::NumberedListItem { rich_text, .. } => {
+                let marker = format!("{number}. ");
+                self.list_item(&marker, marker.len(), rich_text, children)
+            }
+            B::ToDo {
+                rich_text, checked, ..
+            } => {
+                let check = if *checked { 'x' } else { ' ' };
+                self.list_item(&format!("{bullet} [{check}] "), 2, rich_text, children)
+            }
+            B::Toggle { rich_text, .. } => {
+                // Markdown is not rendered inside HTML blocks.
+                let summary = html(rich_text);
+                match self.blocks(children) {
+                    content if content.is_empty() => {
+                        format!("<details>\n<summary>{summary}</summary>\n</details>")
+                    }
+                    content => {
+                        format!(
+                            "<details>\n<summary>{summary}</summary>\n\n{content}\n\n</details>"
+                        )
+                    }
+                }
+            }
+            B::Quote { rich_text, .. } => {
+                quote(&join([paragraph(rich_text), self.blocks(children)]))
+            }
+            B::Callout {
+                rich_text, icon, ..
+            } => {
+                let mut text = paragraph(rich_text);
+                if let Some(Icon::Emoji { emoji }) = icon {
+                    text = format!("{emoji} {text}");
+                }
+                quote(&join([text, self.blocks(children)]))
+            }
+            B::Code {
+                rich_text,
+                language,
+                ..
+            } => code_block(rich_text, language),
+            B::Divider {} => "---".to_string(),
+            B::Equation { expression } => format!("$$\n{}\n$$", expression.trim()),
+            B::Table {
+                has_column_header, ..
+            } => table(children, *has_column_header),
+            B::Image(Media { caption, file, .. }) => {
+                format!(
+                    "![{}]({})",
+                    rich_text_line(caption),
+                    destination(file.url())
+                )
+            }
+            B::Audio(media) | B::File(media) | B::Pdf(media) | B::Video(media) => {
+                let Media {
+                    caption,
+                    file,
+                    name,
+                } = media;
+                let text = match (rich_text_line(caption), name) {
+                    (caption, _) if !caption.is_empty() => caption,
+                    (_, Some(name)) => escape(name),
+                    (_, None) => escape(file_name(file)),
+                };
+                link(&text, file.url())
+            }
+            B::Bookmark { caption, url } | B::Embed { caption, url } => {
+                match rich_text_line(caption) {
+                    caption if caption.is_empty() => format!("<{url}>"),
+                    caption => link(&caption, url),
+                }
+            }
+            B::LinkPreview { url } => format!("<{url}>"),
+            B::LinkToPage(LinkToPage::PageId { page_id }) => {
+                format!("<{}>", notion_url(page_id.as_str()))
+            }
+            B::LinkToPage(LinkToPage::DatabaseId { database_id }) => {
+                format!("<{}>", notion_url(database_id.as_str()))
+            }
+            B::ChildDatabase { title } => link(&escape(title), &notion_url(block.id.as_str())),
+            B::ChildPage { title } => self.child_page(block, title),
+            B::Column { .. } | B::ColumnList { .. } | B::SyncedBlock { .. } => {
+                self.blocks(children)
+            }
+            B::Breadcrumb {}
+            | B::TableOfContents { .. }
+            | B::TableRow { .. }
+            | B::Unsupported {}
+            | B::Unknown { .. } => String::new(),
+        }
+    }
+
+    fn heading(&mut self, level: usize, h: &Heading, children: &[Block]) -> String {
+        join([
+            heading(level, &rich_text_line(&h.rich_text)),
+            self.blocks(children),
+        ])
+    }
+
+    fn list_item(
+        &mut self,
+        marker: &str,
+        width: usize,
+        text: &[RichText],
+        children: &[Block],
+    ) -> String {
+        let mut res = format!("{marker}{}", indent(&paragraph(text), width).trim_start());
+        // Nested lists keep the list tight, anything else would continue the item's text.
+        let nested_list = children
+            .first()
+            .is_some_and(|child| List::of(&child.data).is_some());
+        let children = self.blocks(children);
+        if !children.is_empty() {
+            res.push_str(if nested_list { "\n" } else { "\n\n" });
+            res.push_str(&indent(&children, width));
+        }
+        res
+    }
+
+    fn child_page(&mut self, block: &Block, title: &str) -> String {
+        let url = notion_url(block.id.as_str());
+        // The content was not fetched, so there is nothing to inline.
+        if block.has_children && block.children.is_empty() {
+            return link(&escape(title), &url);
+        }
+
+        let title_heading = heading(1, &escape(title));
+        match self.options.child_pages {
+            ChildPages::Link => link(&escape(title), &url),
+            ChildPages::Inline => join([title_heading, self.blocks(&block.children)]),
+            ChildPages::Files => {
+                let name = format!(
+                    "{} {}.md",
+                    file_title(title),
+                    block.id.as_str().replace('-', "")
+                );
+                // Reserve the spot so the file comes before the files of its own child pages.
+                let index = self.files.len();
+                self.files.push(File {
+                    name: name.clone(),
+                    markdown: String::new(),
+                });
+                self.files[index].markdown = join([title_heading, self.blocks(&block.children)]);
+                link(&escape(title), &name)
+            }
+        }
+    }
+}
+
+/// Joins the non-empty parts with blank lines.
+fn join<const N: usize>(parts: [String; N]) -> String {
+    parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn heading(level: usize, text: &str) -> String {
+    format!("{} {text}", "#".repeat(level))
+}
+
+fn link(text: &str, url: &str) -> String {
+    let text = if text.is_empty() { url } else { text };
+    format!("[{text}]({})", destination(url))
+}
+
+/// A link destination, in angle brackets if it contains spaces or parentheses.
+pub(crate) fn destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{url}>")
+    } else {
+        url.to_string()
+    }
+}
+
+pub(crate) fn notion_url(id: &str) -> String {
+    format!("https://www.notion.so/{}", id.replace('-', ""))
+}
+
+/// Text as inline HTML on a single line.
+fn html(text: &[RichText]) -> String {
+    let res: String = text
+        .iter()
+        .map(|text| {
+            let mut res = escape_html(&text.plain_text).replace('\n', " ");
+            let Annotations {
+                bold,
+                italic,
+                strikethrough,
+                underline,
+                code,
+                ..
+            } = &text.annotations;
+            for (applies, tag) in [
+                (code, "code"),
+                (strikethrough, "del"),
+                (underline, "u"),
+                (italic, "em"),
+                (bold, "strong"),
+            ] {
+                if *applies {
+                    res = format!("<{tag}>{res}</{tag}>");
+                }
+            }
+            match &text.href {
+                Some(href) => format!("<a href=\"{}\">{res}</a>", escape_html(href)),
+                None => res,
+            }
+        })
+        .collect();
+    res.trim().to_string()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Text on a single line, as in headings and table cells.
+fn rich_text_line(text: &[RichText]) -> String {
+    rich_text(text).replace('\n', " ").trim().to_string()
+}
+
+/// Text of a block. Line breaks are kept as hard breaks and lines that would start a block,
+/// e.g. `# Not a heading`, are escaped.
+fn paragraph(text: &[RichText]) -> String {
+    rich_text(text)
+        .split('\n')
+        .map(escape_line_start)
+        .collect::<Vec<_>>()
+        .join("\\\n")
+}
+
+fn escape_line_start(line: &str) -> String {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = &line[digits..];
+    let starts_block = if digits > 0 {
+        rest.starts_with(['.', ')'])
+    } else {
+        rest.starts_with(['#', '>', '+', '-', '='])
+    };
+    if starts_block {
+        format!("{}\\{rest}", &line[..digits])
+    } else {
+        line.to_string()
+    }
+}
+
+/// Indents every line that is not blank by `width`.
+fn indent(text: &str, width: usize) -> String {
+    let pad = " ".repeat(width);
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{pad}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn code_block(text: &[RichText], language: &str) -> String {
+    let code: String = text.iter().map(|text| text.plain_text.as_str()).collect();
+    let fence = "`".repeat(longest_run(&code, '`').max(2) + 1);
+    let language = match language {
+        "plain text" => "",
+        language => language,
+    };
+    format!(
+        "{fence}{language}\n{}\n{fence}",
+        code.trim_end_matches('\n')
+    )
+}
+
+fn table(rows: &[Block], has_column_header: bool) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .filter_map(|row| match &row.data {
+            BlockData::TableRow { cells } => {
+                Some(cells.iter().map(|cell| table_cell(cell)).collect())
+            }
+            _ => None,
+        })
+        .collect();
+    let Some(width) = rows.iter().map(Vec::len).max() else {
+        return String::new();
+    };
+
+    let line = |cells: &[String]| {
+        let mut cells = cells.to_vec();
+        cells.resize(width, String::new());
+        format!("| {} |", cells.join(" | "))
+    };
+
+    // GFM tables always have a header, so an empty one is added if the table has none.
+    let (header, body) = if has_column_header {
+        (line(&rows[0]), &rows[1..])
+    } else {
+        (line(&[]), &rows[..])
+    };
+    let mut res = vec![header, line(&vec!["---".to_string(); width])];
+    res.extend(body.iter().map(|row| line(row)));
+    res.join("\n")
+}
+
+fn table_cell(text: &[RichText]) -> String {
+    rich_text(text)
+        .trim()
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+fn file_name(file: &FileObject) -> &str {
+    let path = file.url().split(['?', '#']).next().unwrap_or_default();
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// `title` without characters that are not allowed in file names on common systems.
+fn file_title(title: &str) -> String {
+    let title: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    match title.split_whitespace().collect::<Vec<_>>().join(" ") {
+        title if title.is_empty() => "Untitled".to_string(),
+        title => title,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, ChildPages, File, Options};
+    use crate::data::{
+        Annotations, Block, BlockData, Color, FileObject, Heading, Icon, Media, PartialUser,
+        RichText,
+    };
+
+    pub(super) fn block(n: u32, data: BlockData, children: Vec<Block>) -> Block {
+        let id = format!("00000000-0000-4000-8000-{n:012}");
+        let payload = serde_json::to_value(&data).unwrap();
+        let user = PartialUser {
+            id: id.parse().unwrap(),
+        };
+        Block {
+            r#type: payload.as_object().unwrap().keys().next().unwrap().clone(),
+            id: id.parse().unwrap(),
+            parent: None,
+            data,
+            created_time: crate::data::DateTime::default(),
+            created_by: user.clone(),
+            last_edited_time: crate::data::DateTime::default(),
+            last_edited_by: user,
+            archived: false,
+            has_children: !children.is_empty(),
+            children,
+        }
+    }
+
+    fn leaf(data: BlockData) -> Block {
+        block(0, data, Vec::new())
+    }
+
+    fn text(text: &str) -> Vec<RichText> {
+        vec![RichText::plain(text)]
+    }
+
+    fn bullet(content: &str) -> BlockData {
+        BlockData::BulletedListItem {
+            rich_text: text(content),
+            color: Color::Default,
+            children: Vec::new(),
+        }
+    }
+
+    fn numbered(content: &str) -> BlockData {
+        BlockData::NumberedListItem {
+            rich_text: text(content),
+            color: Color::Default,
+            children: Vec::new(),
+        }
+    }
+
+    fn to_do(content: &str, checked: bool) -> BlockData {
+        BlockData::ToDo {
+            rich_text: text(content),
+            checked,
+            color: Color::Default,
+            children: Vec::new(),
+        }
+    }
+
+    fn row(cells: &[&str]) -> Block {
+        leaf(BlockData::TableRow {
+            cells: cells.iter().map(|cell| text(cell)).collect(),
+        })
+    }
+
+    fn export(content: &[Block]) -> String {
+        blocks(content, &Options::default()).markdown
+    }
+
+    #[test]
+    fn text_blocks() {
+        let content = [
+            leaf(BlockData::Heading2(Heading {
+                rich_text: text("Tuscan kale"),
+                color: Color::Default,
+                is_toggleable: false,
+                children: Vec::new(),
+            })),
+            leaf(BlockData::paragraph("Serves *2*\n# not a heading")),
+            leaf(BlockData::Quote {
+                rich_text: text("Eat your greens"),
+                color: Color::Default,
+                children: Vec::new(),
+            }),
+            block(
+                1,
+                BlockData::Callout {
+                    rich_text: text("Tip"),
+                    icon: Some(Icon::emoji("💡")),
+                    color: Color::Default,
+                    children: Vec::new(),
+                },
+                vec![leaf(BlockData::paragraph("Salt the water"))],
+            ),
+            leaf(BlockData::Divider {}),
+            leaf(BlockData::Equation {
+                expression: "e = mc^2".to_string(),
+            }),
+            leaf(BlockData::Code {
+                caption: Vec::new(),
+                rich_text: text("fn main() {}\n"),
+                language: "rust".to_string(),
+            }),
+            leaf(BlockData::Code {
+                caption: Vec::new(),
+                rich_text: text("```"),
+                language: "plain text".to_string(),
+            }),
+            leaf(BlockData::TableOfContents {
+                color: Color::Default,
+            }),
+        ];
+        assert_eq!(
+            export(&content),
+            "## Tuscan kale\n\n\
+             Serves \\*2\\*\\\n\\# not a heading\n\n\
+             > Eat your greens\n\n\
+             > 💡 Tip\n>\n> Salt the water\n\n\
+             ---\n\n\
+             $$\ne = mc^2\n$$\n\n\
+             ```rust\nfn main() {}\n```\n\n\
+             ````\n```\n````"
+        );
+    }
+
+    #[test]
+    fn nested_lists() {
+        let content = [
+            block(1, bullet("Wash"), vec![leaf(bullet("Dry"))]),
+            leaf(bullet("Chop")),
+            leaf(numbered("Boil")),
+            block(2, numbered("Simmer"), vec![leaf(numbered("Stir"))]),
+            leaf(to_do("Buy kale", true)),
+            leaf(to_do("Cook", false)),
+            leaf(BlockData::paragraph("Done")),
+            leaf(numbered("Again")),
+            block(
+                3,
+                bullet("Rinse"),
+                vec![leaf(BlockData::paragraph("Twice"))],
+            ),
+        ];
+        // A paragraph right below the item's text would continue it, so it gets a blank line.
+        assert_eq!(
+            export(&content),
+            "- Wash\n  - Dry\n- Chop\n\n\
+             1. Boil\n2. Simmer\n   1. Stir\n\n\
+             - [x] Buy kale\n- [ ] Cook\n\n\
+             Done\n\n\
+             1. Again\n\n\
+             - Rinse\n\n  Twice"
+        );
+    }
+
+    #[test]
+    fn toggles_tables_and_media() {
+        let content = [
+            block(
+                1,
+                BlockData::Toggle {
+                    rich_text: text("Variations"),
+                    color: Color::Default,
+                    children: Vec::new(),
+                },
+                vec![leaf(bullet("Add beans"))],
+            ),
+            block(
+                2,
+                BlockData::Table {
+                    table_width: 2,
+                    has_column_header: true,
+                    has_row_header: false,
+                    children: Vec::new(),
+                },
+                vec![row(&["Item", "Amount"]), row(&["Kale | chard", "1\nbunch"])],
+            ),
+            block(
+                3,
+                BlockData::Table {
+                    table_width: 1,
+                    has_column_header: false,
+                    has_row_header: false,
+                    children: Vec::new(),
+                },
+                vec![row(&["Salt"])],
+            ),
+            leaf(BlockData::Image(Media {
+                caption: text("Kale"),
+                file: FileObject::external("https://example.com/kale.png"),
+                name: None,
+            })),
+            leaf(BlockData::Bookmark {
+                caption: Vec::new(),
+                url: "https://example.com".to_string(),
+            }),
+            leaf(BlockData::Pdf(Media {
+                caption: Vec::new(),
+                file: FileObject::external("https://example.com/recipe.pdf?v=2"),
+                name: None,
+            })),
+        ];
+        assert_eq!(
+            export(&content),
+            "<details>\n<summary>Variations</summary>\n\n- Add beans\n\n</details>\n\n\
+             | Item | Amount |\n| --- | --- |\n| Kale \\| chard | 1<br>bunch |\n\n\
+             |  |\n| --- |\n| Salt |\n\n\
+             ![Kale](https://example.com/kale.png)\n\n\
+             <https://example.com>\n\n\
+             [recipe.pdf](https://example.com/recipe.pdf?v=2)"
+        );
+    }
+
+    #[test]
+    fn toggle_summaries_are_html() {
+        let bold = RichText {
+            annotations: Annotations {
+                bold: true,
+                ..Annotations::default()
+            },
+            ..RichText::plain("Kale & <chard>")
+        };
+        let link = RichText {
+            href: Some("https://example.com/?a=1&b=2".to_string()),
+            ..RichText::plain(" recipes")
+        };
+        let toggle = leaf(BlockData::Toggle {
+            rich_text: vec![bold, link],
+            color: Color::Default,
+            children: Vec::new(),
+        });
+        assert_eq!(
+            export(&[toggle]),
+            "<details>\n<summary><strong>Kale &amp; &lt;chard&gt;</strong>\
+             <a href=\"https://example.com/?a=1&amp;b=2\"> recipes</a></summary>\n</details>"
+        );
+    }
+
+    #[test]
+    fn child_pages() {
+        let page = |n, title: &str, children| {
+            block(
+                n,
+                BlockData::ChildPage {
+                    title: title.to_string(),
+                },
+                children,
+            )
+        };
+        let content = [page(
+            1,
+            "Soups",
+            vec![
+                leaf(BlockData::paragraph("Warm")),
+                page(2, "Kale/bean soup", vec![leaf(BlockData::paragraph("Hot"))]),
+            ],
+        )];
+        let export = |child_pages| blocks(&content, &Options { child_pages });
+
+        assert_eq!(
+            export(ChildPages::Link).markdown,
+            "[Soups](https://www.notion.so/00000000000040008000000000000001)"
+        );
+        assert_eq!(
+            export(ChildPages::Inline).markdown,
+            "# Soups\n\nWarm\n\n# Kale/bean soup\n\nHot"
+        );
+
+        let files = export(ChildPages::Files);
+        assert_eq!(
+            files.markdown,
+            "[Soups](<Soups 00000000000040008000000000000001.md>)"
+        );
+        assert_eq!(
+            files.files,
+            [
+                File {
+                    name: "Soups 00000000000040008000000000000001.md".to_string(),
+                    markdown: "# Soups\n\nWarm\n\n\
+                               [Kale/bean soup](<Kale bean soup 00000000000040008000000000000002.md>)"
+                        .to_string(),
+                },
+                File {
+                    name: "Kale bean soup 00000000000040008000000000000002.md".to_string(),
+                    markdown: "# Kale/bean soup\n\nHot".to_string(),
+                },
+            ]
+        );
+
+        // Not fetched, so there is nothing to inline.
+        let unfetched = Block {
+            has_children: true,
+            ..page(3, "Salads", Vec::new())
+        };
+        assert_eq!(
+            blocks(
+                &[unfetched],
+                &Options {
+                    child_pages: ChildPages::Inline
+                }
+            )
+            .markdown,
+            "[Salads](https://www.notion.so/00000000000040008000000000000003)"
+        );
+    }
+}
@@ -0,0 +1,897 @@
+use pulldown_cmark::{BlockQuoteKind, CodeBlockKind, Event, HeadingLevel, Parser, Tag, TagEnd};
+
+use crate::data::{
+    Annotations, BlockData, Color, FileObject, Heading, Icon, Link, Media, RichText, RichTextData,
+};
+
+/// Notion rejects longer text content in a single rich text object.
+const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Notion rejects more rich text objects in a single block or table cell.
+const MAX_RICH_TEXT: usize = 100;
+
+/// The languages Notion can highlight, as shown in the language picker.
+const LANGUAGES: &[&str] = &[
+    "abap",
+    "agda",
+    "arduino",
+    "ascii art",
+    "assembly",
+    "bash",
+    "basic",
+    "bnf",
+    "c",
+    "c#",
+    "c++",
+    "clojure",
+    "coffeescript",
+    "coq",
+    "css",
+    "dart",
+    "dhall",
+    "diff",
+    "docker",
+    "ebnf",
+    "elixir",
+    "elm",
+    "erlang",
+    "f#",
+    "flow",
+    "fortran",
+    "gherkin",
+    "glsl",
+    "go",
+    "graphql",
+    "groovy",
+    "haskell",
+    "hcl",
+    "html",
+    "idris",
+    "java",
+    "javascript",
+    "json",
+    "julia",
+    "kotlin",
+    "latex",
+    "less",
+    "lisp",
+    "livescript",
+    "llvm ir",
+    "lua",
+    "makefile",
+    "markdown",
+    "markup",
+    "matlab",
+    "mathematica",
+    "mermaid",
+    "nix",
+    "notion formula",
+    "objective-c",
+    "ocaml",
+    "pascal",
+    "perl",
+    "php",
+    "plain text",
+    "powershell",
+    "prolog",
+    "protobuf",
+    "purescript",
+    "python",
+    "r",
+    "racket",
+    "reason",
+    "ruby",
+    "rust",
+    "sass",
+    "scala",
+    "scheme",
+    "scss",
+    "shell",
+    "smalltalk",
+    "solidity",
+    "sql",
+    "swift",
+    "toml",
+    "typescript",
+    "vb.net",
+    "verilog",
+    "vhdl",
+    "visual basic",
+    "webassembly",
+    "xml",
+    "yaml",
+    "java/c/c++/c#",
+];
+
+/// Parses CommonMark with the GitHub extensions (tables, task lists, strikethrough and alerts)
+/// and `$` math into blocks for
+/// [`AppendBlockChildren`](crate::query::AppendBlockChildren).
+///
+/// - Images and `$$` equations on their own line become image and equation blocks.
+/// - Relative links and anchors like `[Install](#install)`, which Notion rejects, are kept as
+///   text. Images that are not on the web become their alt text.
+/// - Alerts like `> [!NOTE]` become callouts.
+/// - Text longer than Notion allows in one rich text object is split. Blocks with more parts
+///   than Notion allows are split into several blocks of the same type. Table cells lose the
+///   formatting of their last parts instead. Text beyond the 200,000 characters Notion accepts
+///   in a cell is dropped.
+/// - Code blocks in languages Notion does not know are `plain text`.
+/// - Headings below level 3 become level 3 headings. HTML is kept as text.
+///
+/// Nesting is kept as written.
+/// [`Client::append_block_children`](crate::Client::append_block_children) appends what does not
+/// fit into one request, e.g. deeper lists or tables with more than 100 rows, in follow-up
+/// requests.
+#[must_use]
+pub fn parse(markdown: &str) -> Vec<BlockData> {
+    use pulldown_cmark::Options;
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH
+        | Options::ENABLE_GFM;
+    let mut importer = Importer {
+        events: Parser::new_ext(markdown, options),
+        tasks: Vec::new(),
+    };
+    importer.blocks().into_iter().flat_map(limit).collect()
+}
+
+/// A piece of inline content, before it is turned into rich text.
+#[derive(Debug)]
+enum Inline {
+    Text {
+        content: String,
+        annotations: Annotations,
+        link: Option<String>,
+    },
+    Equation(String),
+    DisplayMath(String),
+    Image {
+        url: String,
+        alt: String,
+    },
+}
+
+/// Collects inline events, keeping track of the open emphasis and links.
+#[derive(Default)]
+struct Inlines {
+    pieces: Vec<Inline>,
+    annotations: Annotations,
+    /// `None` for links that are kept as text.
+    links: Vec<Option<String>>,
+    /// The URL and alt text of the image being read.
+    image: Option<(String, String)>,
+}
+
+impl Inlines {
+    fn push(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(Tag::Strong) => self.annotations.bold = true,
+            Event::End(TagEnd::Strong) => self.annotations.bold = false,
+            Event::Start(Tag::Emphasis) => self.annotations.italic = true,
+            Event::End(TagEnd::Emphasis) => self.annotations.italic = false,
+            Event::Start(Tag::Strikethrough) => self.annotations.strikethrough = true,
+            Event::End(TagEnd::Strikethrough) => self.annotations.strikethrough = false,
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let url = has_scheme(&dest_url).then(|| dest_url.into_string());
+                self.links.push(url);
+            }
+            Event::End(TagEnd::Link) => {
+                self.links.pop();
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.image = Some((dest_url.into_string(), String::new()));
+            }
+            Event::End(TagEnd::Image) => match self.image.take() {
+                Some((url, alt)) if is_web(&url) => self.pieces.push(Inline::Image { url, alt }),
+                Some((_, alt)) => self.text(&alt),
+                None => {}
+            },
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => self.text(&text),
+            Event::Code(code) => {
+                let annotations = self.annotations.clone();
+                self.annotations.code = true;
+                self.text(&code);
+                self.annotations = annotations;
+            }
+            Event::InlineMath(expression) => match &mut self.image {
+                Some((_, alt)) => alt.push_str(&expression),
+                None => self.pieces.push(Inline::Equation(expression.into_string())),
+            },
+            Event::DisplayMath(expression) => match &mut self.image {
+                Some((_, alt)) => alt.push_str(&expression),
+                None => self
+                    .pieces
+                    .push(Inline::DisplayMath(expression.trim().to_string())),
+            },
+            Event::FootnoteReference(label) => self.text(&format!("[^{label}]")),
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.text("\n"),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some((_, alt)) = &mut self.image {
+            alt.push_str(text);
+            return;
+        }
+
+        let link = self.links.last().and_then(Option::as_ref);
+        // The parser splits text at entities and the like, so it is joined back together.
+        if let Some(Inline::Text {
+            content,
+            annotations,
+            link: last_link,
+        }) = self.pieces.last_mut()
+        {
+            if *annotations == self.annotations && last_link.as_ref() == link {
+                content.push_str(text);
+                return;
+            }
+        }
+        self.pieces.push(Inline::Text {
+            content: text.to_string(),
+            annotations: self.annotations.clone(),
+            link: link.cloned(),
+        });
+    }
+
+    /// A paragraph of text, or image and equation blocks if the paragraph has nothing else.
+    fn into_blocks(self) -> Vec<BlockData> {
+        let standalone = self.pieces.iter().all(|piece| match piece {
+            Inline::Text { content, .. } => content.trim().is_empty(),
+            Inline::Image { .. } | Inline::DisplayMath(_) => true,
+            Inline::Equation(_) => false,
+        });
+        if !standalone {
+            return vec![BlockData::Paragraph {
+                rich_text: rich_text(self.pieces),
+                color: Color::Default,
+                children: Vec::new(),
+            }];
+        }
+
+        self.pieces
+            .into_iter()
+            .filter_map(|piece| match piece {
+                Inline::Image { url, alt } => Some(BlockData::Image(Media {
+                    caption: split(&alt, &Annotations::default(), None),
+                    file: FileObject::external(url),
+                    name: None,
+                })),
+                Inline::DisplayMath(expression) => Some(BlockData::Equation { expression }),
+                Inline::Text { .. } | Inline::Equation(_) => None,
+            })
+            .collect()
+    }
+
+    fn into_rich_text(self) -> Vec<RichText> {
+        rich_text(self.pieces)
+    }
+}
+
+fn rich_text(pieces: Vec<Inline>) -> Vec<RichText> {
+    let mut res = Vec::new();
+    for piece in pieces {
+        match piece {
+            Inline::Text {
+                content,
+                annotations,
+                link,
+            } => res.extend(split(&content, &annotations, link.as_deref())),
+            Inline::Equation(expression) | Inline::DisplayMath(expression) => {
+                res.push(RichText::equation(expression));
+            }
+            Inline::Image { url, alt } => {
+                let text = if alt.is_empty() { url.clone() } else { alt };
+                res.extend(split(&text, &Annotations::default(), Some(&url)));
+            }
+        }
+    }
+
+    // Trailing line breaks, e.g. from a hard break at the end, would show as empty lines.
+    if let Some(RichText {
+        data: RichTextData::Text { content, .. },
+        plain_text,
+        ..
+    }) = res.last_mut()
+    {
+        let len = content.trim_end_matches('\n').len();
+        content.truncate(len);
+        plain_text.truncate(len);
+        if content.is_empty() {
+            res.pop();
+        }
+    }
+    res
+}
+
+/// `text` as rich text, split into parts Notion accepts.
+fn split(text: &str, annotations: &Annotations, link: Option<&str>) -> Vec<RichText> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(MAX_TEXT_LENGTH)
+        .map(|chunk| {
+            let content: String = chunk.iter().collect();
+            RichText {
+                data: RichTextData::Text {
+                    content: content.clone(),
+                    link: link.map(|url| Link {
+                        url: url.to_string(),
+                    }),
+                },
+                annotations: annotations.clone(),
+                href: link.map(str::to_string),
+                ..RichText::plain(content)
+            }
+        })
+        .collect()
+}
+
+struct Importer<'a> {
+    events: Parser<'a>,
+    /// Whether the open list items are checked, if they are tasks.
+    tasks: Vec<Option<bool>>,
+}
+
+impl Importer<'_> {
+    /// Blocks until the end of the current container.
+    fn blocks(&mut self) -> Vec<BlockData> {
+        let mut blocks = Vec::new();
+        // Items of tight lists contain text without a paragraph.
+        let mut text = Inlines::default();
+
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::Start(tag) if !is_inline(&tag) => {
+                    blocks.extend(std::mem::take(&mut text).into_blocks());
+                    blocks.extend(self.block(tag));
+                }
+                Event::End(tag) if !is_inline_end(tag) => break,
+                Event::Rule => {
+                    blocks.extend(std::mem::take(&mut text).into_blocks());
+                    blocks.push(BlockData::Divider {});
+                }
+                Event::TaskListMarker(checked) => self.task(checked),
+                event => text.push(event),
+            }
+        }
+
+        blocks.extend(text.into_blocks());
+        blocks
+    }
+
+    /// Inline content until the end of the current block.
+    fn inlines(&mut self) -> Inlines {
+        let mut text = Inlines::default();
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::End(tag) if !is_inline_end(tag) => break,
+                Event::TaskListMarker(checked) => self.task(checked),
+                event => text.push(event),
+            }
+        }
+        text
+    }
+
+    fn task(&mut self, checked: bool) {
+        if let Some(task) = self.tasks.last_mut() {
+            *task = Some(checked);
+        }
+    }
+
+    fn block(&mut self, tag: Tag<'_>) -> Vec<BlockData> {
+        match tag {
+            Tag::Paragraph => self.inlines().into_blocks(),
+            Tag::Heading { level, .. } => {
+                let heading = Heading {
+                    rich_text: self.inlines().into_rich_text(),
+                    color: Color::Default,
+                    is_toggleable: false,
+                    children: Vec::new(),
+                };
+                vec![match level {
+                    HeadingLevel::H1 => BlockData::Heading1(heading),
+                    HeadingLevel::H2 => BlockData::Heading2(heading),
+                    _ => BlockData::Heading3(heading),
+                }]
+            }
+            Tag::BlockQuote(kind) => {
+                let (rich_text, children) = split_first_paragraph(self.blocks());
+                vec![match kind {
+                    Some(kind) => BlockData::Callout {
+                        rich_text,
+                        icon: Some(Icon::emoji(alert_emoji(kind))),
+                        color: Color::Default,
+                        children,
+                    },
+                    None => BlockData::Quote {
+                        rich_text,
+                        color: Color::Default,
+                        children,
+                    },
+                }]
+            }
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => language(&info),
+                    CodeBlockKind::Indented => "plain text",
+                };
+                let code = self.raw_text();
+                vec![BlockData::Code {
+                    caption: Vec::new(),
+                    rich_text: split(
+                        code.strip_suffix('\n').unwrap_or(&code),
+                        &Annotations::default(),
+                        None,
+                    ),
+                    language: language.to_string(),
+                }]
+            }
+            Tag::HtmlBlock => {
+                let html = self.raw_text();
+                vec![BlockData::Paragraph {
+                    rich_text: split(html.trim_end(), &Annotations::default(), None),
+                    color: Color::Default,
+                    children: Vec::new(),
+                }]
+            }
+            Tag::List(start) => self.list(start.is_some()),
+            Tag::Table(_) => self.table(),
+            // Footnotes, definition lists and metadata are not enabled, so only their content
+            // could end up here.
+            _ => self.blocks(),
+        }
+    }
+
+    /// The text of a code or HTML block.
+    fn raw_text(&mut self) -> String {
+        let mut res = String::new();
+        for event in self.events.by_ref() {
+            match event {
+                Event::Text(text) | Event::Html(text) => res.push_str(&text),
+                Event::End(_) => break,
+                _ => {}
+            }
+        }
+        res
+    }
+
+    fn list(&mut self, ordered: bool) -> Vec<BlockData> {
+        let mut items = Vec::new();
+        while let Some(Event::Start(Tag::Item)) = self.events.next() {
+            self.tasks.push(None);
+            let (rich_text, children) = split_first_paragraph(self.blocks());
+            let color = Color::Default;
+            items.push(match self.tasks.pop().flatten() {
+                Some(checked) => BlockData::ToDo {
+                    rich_text,
+                    checked,
+                    color,
+                    children,
+                },
+                None if ordered => BlockData::NumberedListItem {
+                    rich_text,
+                    color,
+                    children,
+                },
+                None => BlockData::BulletedListItem {
+                    rich_text,
+                    color,
+                    children,
+                },
+            });
+        }
+        items
+    }
+
+    fn table(&mut self) -> Vec<BlockData> {
+        let mut rows: Vec<Vec<Vec<RichText>>> = Vec::new();
+        while let Some(Event::Start(Tag::TableHead | Tag::TableRow)) = self.events.next() {
+            let mut cells = Vec::new();
+            while let Some(Event::Start(Tag::TableCell)) = self.events.next() {
+                cells.push(self.inlines().into_rich_text());
+            }
+            rows.push(cells);
+        }
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let children = rows
+            .into_iter()
+            .map(|mut cells| {
+                cells.resize(width, Vec::new());
+                BlockData::TableRow { cells }
+            })
+            .collect();
+        vec![BlockData::Table {
+            table_width: u32::try_from(width).unwrap_or(u32::MAX),
+            has_column_header: true,
+            has_row_header: false,
+            children,
+        }]
+    }
+}
+
+/// Whether `url` is absolute, e.g. `https://example.com` or `mailto:kale@example.com`.
+fn has_scheme(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Whether Notion can embed the file at `url`.
+fn is_web(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// `block` with its text split into as many blocks as Notion needs, and its children likewise.
+fn limit(mut block: BlockData) -> Vec<BlockData> {
+    if let Some(children) = block.children_mut() {
+        *children = std::mem::take(children)
+            .into_iter()
+            .flat_map(limit)
+            .collect();
+    }
+
+    if let BlockData::TableRow { cells } = &mut block {
+        let plain = |parts: &[RichText]| -> String {
+            parts.iter().map(|text| text.plain_text.as_str()).collect()
+        };
+        for cell in cells.iter_mut().filter(|cell| cell.len() > MAX_RICH_TEXT) {
+            // Keep as many formatted parts as leave room for the rest as unformatted text.
+            let mut keep = MAX_RICH_TEXT - 1;
+            while keep > 0
+                && keep
+                    + plain(&cell[keep..])
+                        .chars()
+                        .count()
+                        .div_ceil(MAX_TEXT_LENGTH)
+                    > MAX_RICH_TEXT
+            {
+                keep -= 1;
+            }
+            let rest = plain(&cell[keep..]);
+            cell.truncate(keep);
+            cell.extend(split(&rest, &Annotations::default(), None));
+            // Only drops text if even unformatted parts cannot hold it.
+            cell.truncate(MAX_RICH_TEXT);
+        }
+    }
+
+    let parts: Vec<Vec<RichText>> = match rich_text_mut(&mut block) {
+        Some(rich_text) if rich_text.len() > MAX_RICH_TEXT => std::mem::take(rich_text)
+            .chunks(MAX_RICH_TEXT)
+            .map(<[_]>::to_vec)
+            .collect(),
+        _ => return vec![block],
+    };
+
+    // The children follow the whole text, so they stay with the last part.
+    let mut first = block.clone();
+    if let Some(children) = first.children_mut() {
+        children.clear();
+    }
+    let mut res: Vec<_> = parts
+        .into_iter()
+        .map(|part| {
+            let mut block = first.clone();
+            *rich_text_mut(&mut block).expect("block has rich text") = part;
+            block
+        })
+        .collect();
+    if let (Some(last), Some(children)) = (res.last_mut(), block.children_mut()) {
+        *last.children_mut().expect("block has children") = std::mem::take(children);
+    }
+    res
+}
+
+/// The text of the blocks [`parse`] creates.
+fn rich_text_mut(block: &mut BlockData) -> Option<&mut Vec<RichText>> {
+    match block {
+        BlockData::BulletedListItem { rich_text, .. }
+        | BlockData::Callout { rich_text, .. }
+        | BlockData::Code { rich_text, .. }
+        | BlockData::NumberedListItem { rich_text, .. }
+        | BlockData::Paragraph { rich_text, .. }
+        | BlockData::Quote { rich_text, .. }
+        | BlockData::ToDo { rich_text, .. } => Some(rich_text),
+        BlockData::Heading1(heading)
+        | BlockData::Heading2(heading)
+        | BlockData::Heading3(heading) => Some(&mut heading.rich_text),
+        _ => None,
+    }
+}
+
+/// The text of a leading paragraph, as used by list items and quotes, and the other blocks.
+fn split_first_paragraph(mut blocks: Vec<BlockData>) -> (Vec<RichText>, Vec<BlockData>) {
+    match blocks.first_mut() {
+        Some(BlockData::Paragraph { rich_text, .. }) => {
+            let rich_text = std::mem::take(rich_text);
+            blocks.remove(0);
+            (rich_text, blocks)
+        }
+        _ => (Vec::new(), blocks),
+    }
+}
+
+const fn is_inline(tag: &Tag<'_>) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+const fn is_inline_end(tag: TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
+
+const fn alert_emoji(kind: BlockQuoteKind) -> &'static str {
+    match kind {
+        BlockQuoteKind::Note => "ℹ️",
+        BlockQuoteKind::Tip => "💡",
+        BlockQuoteKind::Important => "❗",
+        BlockQuoteKind::Warning => "⚠️",
+        BlockQuoteKind::Caution => "🛑",
+    }
+}
+
+/// The Notion language for the info string of a fenced code block.
+fn language(info: &str) -> &'static str {
+    let name = info
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let name = match name.as_str() {
+        "rs" => "rust",
+        "js" | "jsx" | "mjs" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rb" => "ruby",
+        "sh" | "zsh" | "console" => "shell",
+        "ps1" | "pwsh" => "powershell",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "cs" | "csharp" => "c#",
+        "cpp" | "cc" | "cxx" | "hpp" => "c++",
+        "fs" | "fsharp" => "f#",
+        "kt" | "kts" => "kotlin",
+        "golang" => "go",
+        "dockerfile" => "docker",
+        "tex" => "latex",
+        "objc" => "objective-c",
+        "hs" => "haskell",
+        "ex" | "exs" => "elixir",
+        "erl" => "erlang",
+        "ml" => "ocaml",
+        "proto" => "protobuf",
+        "make" => "makefile",
+        "wasm" | "wat" => "webassembly",
+        "tf" => "hcl",
+        "patch" => "diff",
+        "jl" => "julia",
+        name => name,
+    };
+    LANGUAGES
+        .iter()
+        .find(|language| **language == name)
+        .unwrap_or(&"plain text")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, MAX_RICH_TEXT, MAX_TEXT_LENGTH};
+    use crate::{
+        data::{Block, BlockData, RichTextData},
+        markdown::{self, tests::block},
+    };
+
+    fn tree(data: BlockData) -> Block {
+        let children = data.children().iter().cloned().map(tree).collect();
+        block(0, data, children)
+    }
+
+    /// Exported again, which shows the structure more compactly than the blocks themselves.
+    fn round_trip(input: &str) -> String {
+        let blocks: Vec<_> = parse(input).into_iter().map(tree).collect();
+        markdown::blocks(&blocks, &markdown::Options::default()).markdown
+    }
+
+    #[test]
+    fn text_and_annotations() {
+        let input = "# Tuscan kale\n\n\
+                     Some **bold *and italic*** text, `code`, ~~gone~~ and a \
+                     [link](https://example.com). Math: $e^x$\n\
+                     same paragraph\\\nnew line\n\n\
+                     #### Deep";
+        assert_eq!(
+            round_trip(input),
+            "# Tuscan kale\n\n\
+             Some **bold** ***and italic*** text, `code`, ~~gone~~ and a \
+             [link](https://example.com). Math: $e^x$ same paragraph\\\nnew line\n\n\
+             ### Deep"
+        );
+    }
+
+    #[test]
+    fn lists_and_tasks() {
+        let input = "- Wash\n  - Dry\n- Chop\n\n\
+                     1. Boil\n2. Simmer\n\n   Stir often\n\n\
+                     - [x] Buy kale\n- [ ] Cook";
+        assert_eq!(
+            round_trip(input),
+            "- Wash\n  - Dry\n- Chop\n\n\
+             1. Boil\n2. Simmer\n\n   Stir often\n\n\
+             - [x] Buy kale\n- [ ] Cook"
+        );
+    }
+
+    #[test]
+    fn adjacent_bullets_and_tasks_stay_apart() {
+        let input = "- Wash\n- [ ] Chop\n- [x] Boil\n- Serve";
+        let kinds = |blocks: Vec<BlockData>| {
+            blocks
+                .iter()
+                .map(|data| matches!(data, BlockData::ToDo { .. }))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(kinds(parse(input)), [false, true, true, false]);
+
+        let output = round_trip(input);
+        assert_eq!(output, "- Wash\n\n* [ ] Chop\n* [x] Boil\n\n- Serve");
+        assert_eq!(kinds(parse(&output)), [false, true, true, false]);
+        assert_eq!(round_trip(&output), output);
+    }
+
+    #[test]
+    fn code_quotes_tables_and_math() {
+        let input = "```rs\nfn main() {}\n```\n\n\
+                     ```brainfuck\n+++\n```\n\n\
+                     > Eat your greens\n>\n> - really\n\n\
+                     > [!TIP]\n> Salt the water\n\n\
+                     | Item | Amount |\n| --- | --- |\n| Kale | 1 |\n| Salt |\n\n\
+                     $$\ne = mc^2\n$$\n\n\
+                     ![Kale](https://example.com/kale.png)\n\n\
+                     ---";
+        assert_eq!(
+            round_trip(input),
+            "```rust\nfn main() {}\n```\n\n\
+             ```\n+++\n```\n\n\
+             > Eat your greens\n>\n> - really\n\n\
+             > 💡 Salt the water\n\n\
+             | Item | Amount |\n| --- | --- |\n| Kale | 1 |\n| Salt |  |\n\n\
+             $$\ne = mc^2\n$$\n\n\
+             ![Kale](https://example.com/kale.png)\n\n\
+             ---"
+        );
+    }
+
+    #[test]
+    fn relative_links_become_text() {
+        let input =
+            "See [the docs](#install), [notes](notes.md) and [kale](https://example.com).\n\n\
+                     ![](img/a.png)\n\n\
+                     ![Kale](img/kale.png)";
+        assert_eq!(
+            round_trip(input),
+            "See the docs, notes and [kale](https://example.com).\n\nKale"
+        );
+    }
+
+    #[test]
+    fn long_text_is_split() {
+        let long = "kale ".repeat(1000);
+        let blocks = parse(&format!("**{}**", long.trim_end()));
+        let [BlockData::Paragraph { rich_text, .. }] = &blocks[..] else {
+            panic!("expected one paragraph, got {blocks:?}");
+        };
+        let lengths: Vec<_> = rich_text
+            .iter()
+            .map(|text| text.plain_text.chars().count())
+            .collect();
+        assert_eq!(lengths, [MAX_TEXT_LENGTH, MAX_TEXT_LENGTH, 999]);
+        assert!(rich_text.iter().all(|text| text.annotations.bold));
+
+        let code = "x".repeat(MAX_TEXT_LENGTH + 1);
+        let blocks = parse(&format!("```\n{code}\n```"));
+        let [BlockData::Code { rich_text, .. }] = &blocks[..] else {
+            panic!("expected code, got {blocks:?}");
+        };
+        assert_eq!(rich_text.len(), 2);
+        assert!(matches!(
+            &rich_text[1].data,
+            RichTextData::Text { content, .. } if content == "x"
+        ));
+    }
+
+    #[test]
+    fn many_parts_are_split() {
+        let text = "**a** b ".repeat(75);
+        let blocks = parse(&format!("- {}\n  - nested", text.trim_end()));
+        let lengths: Vec<_> = blocks
+            .iter()
+            .map(|block| match block {
+                BlockData::BulletedListItem {
+                    rich_text,
+                    children,
+                    ..
+                } => (rich_text.len(), children.len()),
+                block => panic!("expected list items, got {block:?}"),
+            })
+            .collect();
+        assert_eq!(lengths, [(MAX_RICH_TEXT, 0), (50, 1)]);
+
+        let blocks = parse(&format!("| Notes |\n| --- |\n| {} |", text.trim_end()));
+        let [BlockData::Table { children, .. }] = &blocks[..] else {
+            panic!("expected a table, got {blocks:?}");
+        };
+        let BlockData::TableRow { cells } = &children[1] else {
+            panic!("expected a row, got {:?}", children[1]);
+        };
+        assert_eq!(cells[0].len(), MAX_RICH_TEXT);
+        let last = cells[0].last().unwrap();
+        assert!(!last.annotations.bold);
+        assert!(
+            last.plain_text.starts_with(" b a b "),
+            "{}",
+            last.plain_text
+        );
+    }
+
+    #[test]
+    fn long_table_cells_lose_formatting_first() {
+        let cell = |parts: usize| {
+            let part = "k".repeat(MAX_TEXT_LENGTH / 2);
+            let text = format!("**{part}** {part} ").repeat(parts / 2);
+            let blocks = parse(&format!("| Notes |\n| --- |\n| {} |", text.trim_end()));
+            let [BlockData::Table { children, .. }] = &blocks[..] else {
+                panic!("expected a table, got {blocks:?}");
+            };
+            let BlockData::TableRow { cells } = &children[1] else {
+                panic!("expected a row, got {:?}", children[1]);
+            };
+            let cell = cells[0].clone();
+            let length: usize = cell
+                .iter()
+                .map(|text| text.plain_text.chars().count())
+                .sum();
+            // without the `**` markers
+            (cell, length, text.trim_end().chars().count() - 2 * parts)
+        };
+
+        // 150 parts of 1000 characters fit into 100 once 100 of them are merged
+        let (parts, length, expected) = cell(150);
+        assert_eq!(parts.len(), MAX_RICH_TEXT);
+        assert_eq!(length, expected);
+        assert!(parts[48].annotations.bold);
+        assert!(!parts[50].annotations.bold);
+
+        // more than Notion can store in a cell
+        let (parts, length, expected) = cell(250);
+        assert_eq!(parts.len(), MAX_RICH_TEXT);
+        assert_eq!(length, MAX_RICH_TEXT * MAX_TEXT_LENGTH);
+        assert!(length < expected);
+    }
+}
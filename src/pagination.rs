@@ -0,0 +1,191 @@
+//! <https://developers.notion.com/reference/intro#pagination>
+
+use std::future::Future;
+
+use futures::{stream, Stream, TryStreamExt};
+use serde::Deserialize;
+
+use crate::Result;
+
+/// A single page of a paginated list response.
+#[derive(Deserialize, Debug)]
+pub(crate) struct Chunk<T> {
+    pub results: Vec<T>,
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+enum Cursor {
+    Start,
+    Next(String),
+    Done,
+}
+
+/// Lazily follow `next_cursor`, fetching the next page only once the current one is drained.
+///
+/// `fetch` is called with the cursor to start from, which is `None` for the first page.
+pub(crate) fn paginate<'a, T, F, Fut>(mut fetch: F) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<Chunk<T>>> + 'a,
+{
+    stream::try_unfold(Cursor::Start, move |cursor| {
+        let next = match cursor {
+            Cursor::Start => Some(fetch(None)),
+            Cursor::Next(cursor) => Some(fetch(Some(cursor))),
+            Cursor::Done => None,
+        };
+
+        async move {
+            let Some(next) = next else {
+                return Ok::<_, crate::Error>(None);
+            };
+
+            let Chunk {
+                results,
+                next_cursor,
+                has_more,
+            } = next.await?;
+
+            let cursor = match next_cursor {
+                Some(cursor) if has_more => Cursor::Next(cursor),
+                _ => Cursor::Done,
+            };
+
+            Ok(Some((stream::iter(results.into_iter().map(Ok)), cursor)))
+        }
+    })
+    .try_flatten()
+}
+
+/// Convenience for draining a paginated stream.
+pub trait CollectAll<T>: Stream<Item = Result<T>> + Sized {
+    /// Fetch every page and collect the results, stopping at the first error.
+    fn collect_all(self) -> stream::TryCollect<Self, Vec<T>> {
+        self.try_collect()
+    }
+}
+
+impl<T, S: Stream<Item = Result<T>>> CollectAll<T> for S {}
+
+/// A response of the page property item endpoint.
+///
+/// <https://developers.notion.com/reference/retrieve-a-page-property>
+#[derive(Deserialize, Debug)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub(crate) enum PropertyItems {
+    /// A property that is never paginated, e.g. a number.
+    PropertyItem(serde_json::Map<String, serde_json::Value>),
+    /// One page of the items of a title, rich text, relation, people or rollup property.
+    List {
+        results: Vec<serde_json::Value>,
+        next_cursor: Option<String>,
+        #[serde(default)]
+        has_more: bool,
+        /// The type of the items. For rollups also the aggregated value, complete on the
+        /// last page.
+        property_item: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+/// Turn a property item, or the items of every page, into a value in the format of
+/// [`Property::data`](crate::data::Property::data), e.g. `{ "relation": [...] }`.
+pub(crate) fn join_property_items(
+    mut property_item: serde_json::Map<String, serde_json::Value>,
+    results: Option<Vec<serde_json::Value>>,
+) -> serde_json::Value {
+    let ty = match property_item.get("type") {
+        Some(serde_json::Value::String(ty)) => ty.clone(),
+        _ => String::new(),
+    };
+    let mut payload = property_item.remove(&ty).unwrap_or_default();
+
+    match results {
+        // The items are the rolled up values, which only matter for arrays.
+        Some(results) if ty == "rollup" && payload["type"] == "array" => {
+            payload["array"] = results.into();
+        }
+        Some(_) if ty == "rollup" => {}
+        None => {}
+        // Every item holds a single element, e.g. `{ "type": "relation", "relation": { "id": .. } }`.
+        Some(results) => {
+            payload = results
+                .into_iter()
+                .filter_map(|mut result| result.get_mut(&ty).map(serde_json::Value::take))
+                .collect();
+        }
+    }
+
+    serde_json::json!({ ty: payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use futures::StreamExt;
+
+    use super::{paginate, Chunk, CollectAll};
+
+    fn pages() -> Vec<Chunk<u32>> {
+        vec![
+            Chunk {
+                results: vec![1, 2],
+                next_cursor: Some("a".to_string()),
+                has_more: true,
+            },
+            Chunk {
+                results: vec![3],
+                next_cursor: Some("b".to_string()),
+                has_more: true,
+            },
+            Chunk {
+                results: vec![4, 5],
+                next_cursor: None,
+                has_more: false,
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn follows_cursor() {
+        let mut pages = pages().into_iter();
+        let mut cursors = Vec::new();
+
+        let all = paginate(|cursor| {
+            cursors.push(cursor);
+            let page = pages.next().unwrap();
+            async move { Ok(page) }
+        })
+        .collect_all()
+        .await
+        .unwrap();
+
+        assert_eq!(all, [1, 2, 3, 4, 5]);
+        assert_eq!(
+            cursors,
+            [None, Some("a".to_string()), Some("b".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetches_lazily() {
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let mut pages = pages().into_iter();
+
+        let stream = paginate(|_| {
+            fetched.fetch_add(1, Ordering::SeqCst);
+            let page = pages.next().unwrap();
+            async move { Ok(page) }
+        });
+
+        let first: Vec<_> = stream.take(2).collect().await;
+        assert_eq!(first.len(), 2);
+        assert_eq!(fetched.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,13 +1,26 @@
+use std::collections::HashMap;
+
 use serde::Serialize;
+use serde_with::skip_serializing_none;
+
+use crate::{
+    data::{
+        parent_object::Parent, BlockData, FileObject, Icon, PropertyConfig, PropertyValue, RichText,
+    },
+    BlockId, DatabaseId, PageId,
+};
 
+pub mod filter;
+
+/// <https://developers.notion.com/reference/intro#pagination>
 #[derive(Debug, Clone, Serialize)]
-pub struct BlockChildren {
+pub struct Pagination {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start_cursor: Option<String>,
     pub page_size: u32,
 }
 
-impl Default for BlockChildren {
+impl Default for Pagination {
     fn default() -> Self {
         Self {
             start_cursor: None,
@@ -15,3 +28,463 @@ impl Default for BlockChildren {
         }
     }
 }
+
+/// <https://developers.notion.com/reference/get-block-children>
+pub type BlockChildren = Pagination;
+
+/// <https://developers.notion.com/reference/get-users>
+pub type ListUsers = Pagination;
+
+/// <https://developers.notion.com/reference/retrieve-a-comment>
+pub type ListComments = Pagination;
+
+/// <https://developers.notion.com/reference/create-a-comment>
+///
+/// Either starts a new discussion on a page or replies to an existing discussion.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateComment {
+    /// Set for a new discussion. Must be a page.
+    pub parent: Option<Parent>,
+    /// Set for a reply.
+    pub discussion_id: Option<String>,
+    pub rich_text: Vec<RichText>,
+}
+
+impl CreateComment {
+    /// A new page-level discussion.
+    pub fn on_page(page_id: impl Into<PageId>, text: impl Into<String>) -> Self {
+        Self {
+            parent: Some(Parent::page(page_id)),
+            discussion_id: None,
+            rich_text: vec![RichText::plain(text)],
+        }
+    }
+
+    /// A reply to the discussion of [`Comment::discussion_id`](crate::data::Comment::discussion_id).
+    pub fn reply(discussion_id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            parent: None,
+            discussion_id: Some(discussion_id.into()),
+            rich_text: vec![RichText::plain(text)],
+        }
+    }
+}
+
+/// <https://developers.notion.com/reference/post-database-query>
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseQuery {
+    pub filter: Option<filter::Filter>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sorts: Vec<filter::Sort>,
+    pub start_cursor: Option<String>,
+    /// The maximum number of results to return. Default: 100. Maximum: 100.
+    pub page_size: Option<u32>,
+}
+
+impl Default for DatabaseQuery {
+    fn default() -> Self {
+        Self {
+            filter: None,
+            sorts: Vec::new(),
+            start_cursor: None,
+            page_size: Some(100),
+        }
+    }
+}
+
+/// <https://developers.notion.com/reference/create-a-database>
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateDatabase {
+    /// Must be a page.
+    pub parent: Parent,
+    pub title: Vec<RichText>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub description: Vec<RichText>,
+    /// Map: property name -> configuration. Exactly one property must be a title.
+    pub properties: HashMap<String, PropertyConfig>,
+    pub icon: Option<Icon>,
+    pub cover: Option<FileObject>,
+    pub is_inline: Option<bool>,
+}
+
+impl CreateDatabase {
+    /// A database under the page `parent_page_id` with a single title property called `Name`.
+    pub fn new(parent_page_id: impl Into<PageId>, title: impl Into<String>) -> Self {
+        Self {
+            parent: Parent::page(parent_page_id),
+            title: vec![RichText::plain(title)],
+            description: Vec::new(),
+            properties: HashMap::from([("Name".to_string(), PropertyConfig::Title {})]),
+            icon: None,
+            cover: None,
+            is_inline: None,
+        }
+    }
+
+    #[must_use]
+    pub fn property(mut self, name: impl Into<String>, config: PropertyConfig) -> Self {
+        self.properties.insert(name.into(), config);
+        self
+    }
+}
+
+/// <https://developers.notion.com/reference/update-a-database>
+///
+/// ```
+/// use notion_rs::{data::PropertyConfig, query::UpdateDatabase};
+///
+/// let update = UpdateDatabase::default()
+///     .add_property("Done", PropertyConfig::Checkbox {})
+///     .rename_property("Tags", "Labels")
+///     .remove_property("Old");
+/// ```
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateDatabase {
+    pub title: Option<Vec<RichText>>,
+    pub description: Option<Vec<RichText>>,
+    /// Map: property name or id -> update. `None` removes the property.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, Option<PropertyUpdate>>,
+    pub icon: Option<Icon>,
+    pub cover: Option<FileObject>,
+    pub archived: Option<bool>,
+}
+
+impl UpdateDatabase {
+    /// Add a property, or change the type of an existing one.
+    #[must_use]
+    pub fn add_property(mut self, name: impl Into<String>, config: PropertyConfig) -> Self {
+        self.properties.insert(
+            name.into(),
+            Some(PropertyUpdate {
+                name: None,
+                config: Some(config),
+            }),
+        );
+        self
+    }
+
+    /// Rename the property with the given name or id.
+    #[must_use]
+    pub fn rename_property(
+        mut self,
+        name_or_id: impl Into<String>,
+        new_name: impl Into<String>,
+    ) -> Self {
+        self.properties.insert(
+            name_or_id.into(),
+            Some(PropertyUpdate {
+                name: Some(new_name.into()),
+                config: None,
+            }),
+        );
+        self
+    }
+
+    /// Remove the property with the given name or id.
+    #[must_use]
+    pub fn remove_property(mut self, name_or_id: impl Into<String>) -> Self {
+        self.properties.insert(name_or_id.into(), None);
+        self
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyUpdate {
+    /// The new name of the property.
+    pub name: Option<String>,
+    /// The new type and configuration of the property.
+    #[serde(flatten)]
+    pub config: Option<PropertyConfig>,
+}
+
+/// <https://developers.notion.com/reference/post-page>
+///
+/// ```
+/// use notion_rs::{data::PropertyValue, query::CreatePage, DatabaseId};
+///
+/// let database: DatabaseId = "https://www.notion.so/d9824bdc84454327be8b5b47500af6ce".parse()?;
+/// let page = CreatePage::in_database(database)
+///     .property("Name", PropertyValue::title("Tuscan kale"))
+///     .property("Price", PropertyValue::Number(Some(2.5)))
+///     .property("Tags", PropertyValue::multi_select(["vegetable"]));
+/// # Ok::<(), notion_rs::ParseIdError>(())
+/// ```
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePage {
+    pub parent: Parent,
+    /// Map: property name or id -> value. Pages under a page can only have a `title`.
+    pub properties: HashMap<String, PropertyValue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<BlockData>,
+    pub icon: Option<Icon>,
+    pub cover: Option<FileObject>,
+}
+
+impl CreatePage {
+    /// A page under the page `page_id` with the given title.
+    pub fn in_page(page_id: impl Into<PageId>, title: impl Into<String>) -> Self {
+        Self {
+            parent: Parent::page(page_id),
+            properties: HashMap::from([("title".to_string(), PropertyValue::title(title))]),
+            children: Vec::new(),
+            icon: None,
+            cover: None,
+        }
+    }
+
+    /// A row in the database `database_id`. Set the title with [`CreatePage::property`].
+    pub fn in_database(database_id: impl Into<DatabaseId>) -> Self {
+        Self {
+            parent: Parent::database(database_id),
+            properties: HashMap::new(),
+            children: Vec::new(),
+            icon: None,
+            cover: None,
+        }
+    }
+
+    #[must_use]
+    pub fn property(mut self, name_or_id: impl Into<String>, value: PropertyValue) -> Self {
+        self.properties.insert(name_or_id.into(), value);
+        self
+    }
+
+    /// The initial content of the page.
+    #[must_use]
+    pub fn children(mut self, children: Vec<BlockData>) -> Self {
+        self.children = children;
+        self
+    }
+
+    #[must_use]
+    pub fn icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    #[must_use]
+    pub fn cover(mut self, cover: FileObject) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+}
+
+/// <https://developers.notion.com/reference/patch-page>
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdatePage {
+    /// Map: property name or id -> value. Properties that are not set are left unchanged.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, PropertyValue>,
+    pub archived: Option<bool>,
+    /// `Some(None)` removes the icon.
+    pub icon: Option<Option<Icon>>,
+    /// `Some(None)` removes the cover.
+    pub cover: Option<Option<FileObject>>,
+}
+
+impl UpdatePage {
+    #[must_use]
+    pub fn property(mut self, name_or_id: impl Into<String>, value: PropertyValue) -> Self {
+        self.properties.insert(name_or_id.into(), value);
+        self
+    }
+
+    /// Move the page to the trash.
+    #[must_use]
+    pub const fn archive(mut self) -> Self {
+        self.archived = Some(true);
+        self
+    }
+
+    /// Restore the page from the trash.
+    #[must_use]
+    pub const fn unarchive(mut self) -> Self {
+        self.archived = Some(false);
+        self
+    }
+
+    /// Replace the icon, or remove it with `None`.
+    #[must_use]
+    pub fn icon(mut self, icon: Option<Icon>) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Replace the cover, or remove it with `None`.
+    #[must_use]
+    pub fn cover(mut self, cover: Option<FileObject>) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+}
+
+/// <https://developers.notion.com/reference/patch-block-children>
+#[derive(Debug, Clone, Default)]
+pub struct AppendBlockChildren {
+    /// Any number of blocks, nested as deep as needed. They are sent in chunks of 100, in order.
+    ///
+    /// Nesting beyond two levels and children past the 100th of a block are sent in extra
+    /// requests, so the append is not atomic: if one of them fails, the blocks sent before
+    /// stay appended.
+    pub children: Vec<BlockData>,
+    /// Insert after the child with this id instead of at the end.
+    pub after: Option<BlockId>,
+}
+
+impl AppendBlockChildren {
+    #[must_use]
+    pub fn new(children: Vec<BlockData>) -> Self {
+        Self {
+            children,
+            after: None,
+        }
+    }
+
+    /// Insert after the child with the given id instead of at the end.
+    #[must_use]
+    pub fn after(mut self, block_id: impl Into<BlockId>) -> Self {
+        self.after = Some(block_id.into());
+        self
+    }
+}
+
+/// Options for [`Client::get_page_with`](crate::Client::get_page_with).
+#[derive(Debug, Clone, Default)]
+pub struct GetPage {
+    /// Replace properties that may be truncated, see
+    /// [`Property::may_be_truncated`](crate::data::Property::may_be_truncated), with their
+    /// complete value from [`Client::get_page_property`](crate::Client::get_page_property).
+    pub hydrate_properties: bool,
+}
+
+/// <https://developers.notion.com/reference/retrieve-a-page-property>
+pub type PropertyItems = Pagination;
+
+/// Options for [`Client::block_tree`](crate::Client::block_tree).
+#[derive(Debug, Clone)]
+pub struct BlockTree {
+    /// How many levels of children to fetch. `None` fetches everything.
+    ///
+    /// The direct children are always fetched, so `Some(0)` and `Some(1)` both return only the
+    /// top level.
+    pub max_depth: Option<usize>,
+    /// The maximum number of blocks whose children are fetched at the same time.
+    pub concurrency: usize,
+    /// Fetch the content of child pages.
+    pub child_pages: bool,
+    /// Fetch the rows of child databases as `child_page` blocks. Their content is only fetched
+    /// if [`BlockTree::child_pages`] is set as well.
+    pub child_databases: bool,
+    /// Fetch the content of synced blocks. For references to a synced block, this is the content
+    /// of the original.
+    pub synced_blocks: bool,
+}
+
+impl Default for BlockTree {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            concurrency: 4,
+            child_pages: false,
+            child_databases: false,
+            synced_blocks: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{CreateComment, CreatePage, UpdateDatabase, UpdatePage};
+    use crate::{
+        data::{DateValue, PropertyConfig, PropertyValue, SelectConfig, SelectOption},
+        DatabaseId, PageId,
+    };
+
+    #[test]
+    fn serialize_update_database() {
+        let update = UpdateDatabase::default()
+            .add_property(
+                "Tags",
+                PropertyConfig::MultiSelect(SelectConfig {
+                    options: vec![SelectOption::new("urgent")],
+                }),
+            )
+            .rename_property("evWq", "Cost")
+            .remove_property("Old");
+
+        let expected = json!({
+            "properties": {
+                "Tags": { "multi_select": { "options": [{ "name": "urgent" }] } },
+                "evWq": { "name": "Cost" },
+                "Old": null
+            }
+        });
+
+        assert_eq!(serde_json::to_value(update).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_create_page() {
+        let database_id: DatabaseId = "d9824bdc-8445-4327-be8b-5b47500af6ce".parse().unwrap();
+        let page = CreatePage::in_database(database_id)
+            .property("Price", PropertyValue::Number(Some(2.5)))
+            .property("Tags", PropertyValue::multi_select(["vegetable"]))
+            .property(
+                "Recipes",
+                PropertyValue::relation(["c02fc1d3db8b45c5a22227595b15aea7".parse().unwrap()]),
+            )
+            .property(
+                "Due",
+                PropertyValue::date("2023-03-08".parse::<DateValue>().unwrap()),
+            )
+            .property("Website", PropertyValue::Url(None));
+
+        let expected = json!({
+            "parent": { "type": "database_id", "database_id": "d9824bdc-8445-4327-be8b-5b47500af6ce" },
+            "properties": {
+                "Price": { "number": 2.5 },
+                "Tags": { "multi_select": [{ "name": "vegetable" }] },
+                "Recipes": { "relation": [{ "id": "c02fc1d3-db8b-45c5-a222-27595b15aea7" }] },
+                "Due": { "date": { "start": "2023-03-08", "end": null, "time_zone": null } },
+                "Website": { "url": null }
+            }
+        });
+
+        assert_eq!(serde_json::to_value(page).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_update_page() {
+        let update = UpdatePage::default().archive().icon(None);
+
+        let expected = json!({ "archived": true, "icon": null });
+
+        assert_eq!(serde_json::to_value(update).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_create_comment() {
+        let page_id: PageId = "59833787-2cf9-4fdf-8782-e53db20768a5".parse().unwrap();
+        let comment = serde_json::to_value(CreateComment::on_page(page_id, "Nice")).unwrap();
+        assert_eq!(
+            comment["parent"],
+            json!({ "type": "page_id", "page_id": "59833787-2cf9-4fdf-8782-e53db20768a5" })
+        );
+        assert!(comment.get("discussion_id").is_none());
+
+        let reply = serde_json::to_value(CreateComment::reply("d1", "Thanks")).unwrap();
+        assert_eq!(reply["discussion_id"], "d1");
+        assert!(reply.get("parent").is_none());
+        assert_eq!(reply["rich_text"][0]["text"]["content"], "Thanks");
+    }
+}
@@ -0,0 +1,438 @@
+//! - [Filter database entries](https://developers.notion.com/reference/post-database-query-filter)
+//! - [Sort database entries](https://developers.notion.com/reference/post-database-query-sort)
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use notion_rs::query::filter::{DateCondition, Filter, NumberCondition, Sort, TextCondition};
+//!
+//! let new_year = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+//!
+//! let filter = Filter::and([
+//!     Filter::checkbox("Done", false),
+//!     Filter::or([
+//!         Filter::rich_text("Tags", TextCondition::Contains("urgent".into())),
+//!         Filter::number("Priority", NumberCondition::GreaterThan(2.0)),
+//!     ]),
+//!     Filter::created_time(DateCondition::OnOrAfter(new_year.into())),
+//! ]);
+//!
+//! let sorts = [Sort::descending("Priority"), Sort::created_time_ascending()];
+//! ```
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::data::{DateValue, SortDirection};
+
+/// A filter for [`DatabaseQuery`](super::DatabaseQuery).
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Filter {
+    And {
+        and: Vec<Filter>,
+    },
+    Or {
+        or: Vec<Filter>,
+    },
+    Property {
+        /// The name or id of the property.
+        property: String,
+        #[serde(flatten)]
+        condition: PropertyCondition,
+    },
+    Timestamp(TimestampCondition),
+}
+
+impl Filter {
+    /// Match entries that match all of `filters`.
+    pub fn and(filters: impl IntoIterator<Item = Self>) -> Self {
+        Self::And {
+            and: filters.into_iter().collect(),
+        }
+    }
+
+    /// Match entries that match any of `filters`.
+    pub fn or(filters: impl IntoIterator<Item = Self>) -> Self {
+        Self::Or {
+            or: filters.into_iter().collect(),
+        }
+    }
+
+    /// Match entries whose property `property` matches `condition`.
+    pub fn property(property: impl Into<String>, condition: PropertyCondition) -> Self {
+        Self::Property {
+            property: property.into(),
+            condition,
+        }
+    }
+
+    pub fn checkbox(property: impl Into<String>, equals: bool) -> Self {
+        Self::property(
+            property,
+            PropertyCondition::Checkbox(CheckboxCondition::Equals(equals)),
+        )
+    }
+
+    pub fn date(property: impl Into<String>, condition: DateCondition) -> Self {
+        Self::property(property, PropertyCondition::Date(condition))
+    }
+
+    pub fn number(property: impl Into<String>, condition: NumberCondition) -> Self {
+        Self::property(property, PropertyCondition::Number(condition))
+    }
+
+    pub fn rich_text(property: impl Into<String>, condition: TextCondition) -> Self {
+        Self::property(property, PropertyCondition::RichText(condition))
+    }
+
+    pub fn title(property: impl Into<String>, condition: TextCondition) -> Self {
+        Self::property(property, PropertyCondition::Title(condition))
+    }
+
+    pub fn select(property: impl Into<String>, condition: SelectCondition) -> Self {
+        Self::property(property, PropertyCondition::Select(condition))
+    }
+
+    pub fn status(property: impl Into<String>, condition: SelectCondition) -> Self {
+        Self::property(property, PropertyCondition::Status(condition))
+    }
+
+    pub fn multi_select(property: impl Into<String>, condition: ContainsCondition) -> Self {
+        Self::property(property, PropertyCondition::MultiSelect(condition))
+    }
+
+    pub fn people(property: impl Into<String>, condition: ContainsCondition) -> Self {
+        Self::property(property, PropertyCondition::People(condition))
+    }
+
+    pub fn relation(property: impl Into<String>, condition: ContainsCondition) -> Self {
+        Self::property(property, PropertyCondition::Relation(condition))
+    }
+
+    /// Match entries by when they were created.
+    pub const fn created_time(condition: DateCondition) -> Self {
+        Self::Timestamp(TimestampCondition::CreatedTime(condition))
+    }
+
+    /// Match entries by when they were last edited.
+    pub const fn last_edited_time(condition: DateCondition) -> Self {
+        Self::Timestamp(TimestampCondition::LastEditedTime(condition))
+    }
+}
+
+/// The condition of a property filter, by property type.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyCondition {
+    Checkbox(CheckboxCondition),
+    CreatedBy(ContainsCondition),
+    Date(DateCondition),
+    Email(TextCondition),
+    Files(EmptyCondition),
+    Formula(FormulaCondition),
+    LastEditedBy(ContainsCondition),
+    MultiSelect(ContainsCondition),
+    Number(NumberCondition),
+    People(ContainsCondition),
+    PhoneNumber(TextCondition),
+    Relation(ContainsCondition),
+    RichText(TextCondition),
+    Select(SelectCondition),
+    Status(SelectCondition),
+    Title(TextCondition),
+    Url(TextCondition),
+}
+
+/// A filter on `created_time` or `last_edited_time`.
+///
+/// Serializes as `{ "timestamp": "created_time", "created_time": { ... } }`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimestampCondition {
+    CreatedTime(DateCondition),
+    LastEditedTime(DateCondition),
+}
+
+impl Serialize for TimestampCondition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (timestamp, condition) = match self {
+            Self::CreatedTime(condition) => (Timestamp::CreatedTime, condition),
+            Self::LastEditedTime(condition) => (Timestamp::LastEditedTime, condition),
+        };
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("timestamp", &timestamp)?;
+        map.serialize_entry(timestamp.as_str(), condition)?;
+        map.end()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Timestamp {
+    CreatedTime,
+    LastEditedTime,
+}
+
+impl Timestamp {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::CreatedTime => "created_time",
+            Self::LastEditedTime => "last_edited_time",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckboxCondition {
+    Equals(bool),
+    DoesNotEqual(bool),
+}
+
+/// Dates are sent as ISO 8601, either a date (`2023-03-08`) or a date time
+/// (`2023-03-08T18:25:00.000Z`). Use `.into()` on a [`NaiveDate`](chrono::NaiveDate) or
+/// [`DateTime`](chrono::DateTime), or parse a string into a [`DateValue`].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateCondition {
+    Equals(DateValue),
+    Before(DateValue),
+    After(DateValue),
+    OnOrBefore(DateValue),
+    OnOrAfter(DateValue),
+    #[serde(serialize_with = "true_")]
+    IsEmpty,
+    #[serde(serialize_with = "true_")]
+    IsNotEmpty,
+    #[serde(serialize_with = "empty_object")]
+    PastWeek,
+    #[serde(serialize_with = "empty_object")]
+    PastMonth,
+    #[serde(serialize_with = "empty_object")]
+    PastYear,
+    #[serde(serialize_with = "empty_object")]
+    ThisWeek,
+    #[serde(serialize_with = "empty_object")]
+    NextWeek,
+    #[serde(serialize_with = "empty_object")]
+    NextMonth,
+    #[serde(serialize_with = "empty_object")]
+    NextYear,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberCondition {
+    Equals(f64),
+    DoesNotEqual(f64),
+    GreaterThan(f64),
+    LessThan(f64),
+    GreaterThanOrEqualTo(f64),
+    LessThanOrEqualTo(f64),
+    #[serde(serialize_with = "true_")]
+    IsEmpty,
+    #[serde(serialize_with = "true_")]
+    IsNotEmpty,
+}
+
+/// Used for rich text, title, URL, email and phone number properties.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TextCondition {
+    Equals(String),
+    DoesNotEqual(String),
+    Contains(String),
+    DoesNotContain(String),
+    StartsWith(String),
+    EndsWith(String),
+    #[serde(serialize_with = "true_")]
+    IsEmpty,
+    #[serde(serialize_with = "true_")]
+    IsNotEmpty,
+}
+
+/// Used for select and status properties. Compares against the option name.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectCondition {
+    Equals(String),
+    DoesNotEqual(String),
+    #[serde(serialize_with = "true_")]
+    IsEmpty,
+    #[serde(serialize_with = "true_")]
+    IsNotEmpty,
+}
+
+/// Used for multi-select (option name), people (user id) and relation (page id) properties.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainsCondition {
+    Contains(String),
+    DoesNotContain(String),
+    #[serde(serialize_with = "true_")]
+    IsEmpty,
+    #[serde(serialize_with = "true_")]
+    IsNotEmpty,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyCondition {
+    #[serde(serialize_with = "true_")]
+    IsEmpty,
+    #[serde(serialize_with = "true_")]
+    IsNotEmpty,
+}
+
+/// Filters on the result of a formula, by result type.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FormulaCondition {
+    Checkbox(CheckboxCondition),
+    Date(DateCondition),
+    Number(NumberCondition),
+    String(TextCondition),
+}
+
+/// A sort for [`DatabaseQuery`](super::DatabaseQuery). Earlier sorts take precedence.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Sort {
+    Property {
+        /// The name or id of the property.
+        property: String,
+        direction: SortDirection,
+    },
+    Timestamp {
+        timestamp: Timestamp,
+        direction: SortDirection,
+    },
+}
+
+impl Sort {
+    pub fn ascending(property: impl Into<String>) -> Self {
+        Self::Property {
+            property: property.into(),
+            direction: SortDirection::Ascending,
+        }
+    }
+
+    pub fn descending(property: impl Into<String>) -> Self {
+        Self::Property {
+            property: property.into(),
+            direction: SortDirection::Descending,
+        }
+    }
+
+    #[must_use]
+    pub const fn created_time_ascending() -> Self {
+        Self::Timestamp {
+            timestamp: Timestamp::CreatedTime,
+            direction: SortDirection::Ascending,
+        }
+    }
+
+    #[must_use]
+    pub const fn created_time_descending() -> Self {
+        Self::Timestamp {
+            timestamp: Timestamp::CreatedTime,
+            direction: SortDirection::Descending,
+        }
+    }
+
+    #[must_use]
+    pub const fn last_edited_time_ascending() -> Self {
+        Self::Timestamp {
+            timestamp: Timestamp::LastEditedTime,
+            direction: SortDirection::Ascending,
+        }
+    }
+
+    #[must_use]
+    pub const fn last_edited_time_descending() -> Self {
+        Self::Timestamp {
+            timestamp: Timestamp::LastEditedTime,
+            direction: SortDirection::Descending,
+        }
+    }
+}
+
+/// `"is_empty": true`
+fn true_<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bool(true)
+}
+
+/// `"past_week": {}`
+fn empty_object<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_map(Some(0))?.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use serde_json::json;
+
+    use super::{
+        ContainsCondition, DateCondition, Filter, FormulaCondition, NumberCondition, Sort,
+        TextCondition,
+    };
+    use crate::query::{filter::PropertyCondition, DatabaseQuery};
+
+    #[test]
+    fn serialize_compound_filter() {
+        let due = NaiveDate::from_ymd_opt(2023, 3, 8).unwrap();
+        let edited = Utc.with_ymd_and_hms(2023, 3, 8, 18, 25, 0).unwrap();
+        let filter = Filter::and([
+            Filter::checkbox("Done", true),
+            Filter::or([
+                Filter::rich_text("Name", TextCondition::Contains("meeting".into())),
+                Filter::number("Cost", NumberCondition::GreaterThan(2.5)),
+                Filter::relation("Project", ContainsCondition::IsNotEmpty),
+            ]),
+            Filter::date("Due", DateCondition::OnOrAfter(due.into())),
+            Filter::date("Due", DateCondition::PastWeek),
+            Filter::property(
+                "Total",
+                PropertyCondition::Formula(FormulaCondition::Number(NumberCondition::IsEmpty)),
+            ),
+            Filter::last_edited_time(DateCondition::Before(edited.into())),
+        ]);
+
+        let expected = json!({
+            "and": [
+                { "property": "Done", "checkbox": { "equals": true } },
+                { "or": [
+                    { "property": "Name", "rich_text": { "contains": "meeting" } },
+                    { "property": "Cost", "number": { "greater_than": 2.5 } },
+                    { "property": "Project", "relation": { "is_not_empty": true } },
+                ] },
+                { "property": "Due", "date": { "on_or_after": "2023-03-08" } },
+                { "property": "Due", "date": { "past_week": {} } },
+                { "property": "Total", "formula": { "number": { "is_empty": true } } },
+                {
+                    "timestamp": "last_edited_time",
+                    "last_edited_time": { "before": "2023-03-08T18:25:00.000Z" }
+                },
+            ]
+        });
+
+        assert_eq!(serde_json::to_value(filter).unwrap(), expected);
+    }
+
+    #[test]
+    fn serialize_query() {
+        let query = DatabaseQuery {
+            sorts: vec![Sort::ascending("Name"), Sort::created_time_descending()],
+            ..DatabaseQuery::default()
+        };
+
+        let expected = json!({
+            "sorts": [
+                { "property": "Name", "direction": "ascending" },
+                { "timestamp": "created_time", "direction": "descending" },
+            ],
+            "page_size": 100
+        });
+
+        assert_eq!(serde_json::to_value(query).unwrap(), expected);
+    }
+}
@@ -0,0 +1,362 @@
+//! - [Request limits](https://developers.notion.com/reference/request-limits)
+//! - [Status codes](https://developers.notion.com/reference/status-codes)
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rand::Rng;
+use reqwest::{header::HeaderMap, header::RETRY_AFTER, Method, StatusCode};
+use tokio::time::Instant;
+
+/// When and how long to wait before retrying a failed request.
+///
+/// - `429 Too Many Requests` is retried after `Retry-After` if Notion sent one.
+/// - `409 Conflict` (`conflict_error`) and `5xx` are retried with jittered exponential backoff,
+///   but only for `GET`, `PATCH` and `DELETE`. `POST` requests, which create pages, databases and
+///   comments, may have been applied before the error, so they are only retried on `429`. This
+///   includes searches and database queries.
+///
+/// Requests that fail before a response arrives (e.g. connection errors) are never retried, since
+/// a write may already have been applied.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// The number of retries after the first attempt. `0` disables retrying.
+    pub max_retries: u32,
+    /// The backoff before the first retry. Doubles on every retry after that.
+    pub initial_backoff: Duration,
+    /// The upper bound for a single backoff, including `Retry-After`.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// How long to wait before retry number `attempt + 1`, or `None` if the response should be
+    /// returned as is.
+    pub(crate) fn delay(
+        &self,
+        attempt: u32,
+        method: &Method,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = retry_after(headers).map(|delay| delay.min(self.max_backoff));
+                Some(retry_after.unwrap_or_else(|| self.backoff(attempt)))
+            }
+            _ if *method == Method::POST => None,
+            StatusCode::CONFLICT => Some(self.backoff(attempt)),
+            status if status.is_server_error() => Some(self.backoff(attempt)),
+            _ => None,
+        }
+    }
+
+    /// Exponential backoff with "equal jitter": half of the delay is fixed, the other half random.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+
+        let half = backoff / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+}
+
+/// `Retry-After` in seconds. Notion does not send the HTTP-date form.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?;
+    let secs: f64 = value.trim().parse().ok()?;
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// Settings for the client-side token bucket.
+///
+/// Notion allows an average of three requests per second per integration, with some bursts.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// The rate at which tokens are refilled. Must be positive.
+    pub requests_per_second: f64,
+    /// The number of requests that can be made at once after being idle.
+    pub burst: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 3.0,
+            burst: 3,
+        }
+    }
+}
+
+/// A token bucket. Shared between clones of a [`Client`](crate::Client).
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    state: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Can go negative: every caller reserves its token up front and then sleeps until the
+    /// bucket would have refilled.
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(Bucket {
+                tokens: f64::from(limit.burst),
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent.
+    pub(crate) async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.state.lock();
+            let now = Instant::now();
+
+            let refill =
+                now.duration_since(bucket.updated).as_secs_f64() * self.limit.requests_per_second;
+            bucket.tokens = (bucket.tokens + refill).min(f64::from(self.limit.burst));
+            bucket.updated = now;
+
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+
+            Duration::from_secs_f64(-bucket.tokens / self.limit.requests_per_second)
+        };
+
+        tracing::debug!(?wait, "rate limited");
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::{header::HeaderMap, Method, StatusCode};
+    use tokio::time::Instant;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::{RateLimit, RateLimiter, RetryPolicy};
+    use crate::{Client, ErrorCode};
+
+    fn users() -> serde_json::Value {
+        serde_json::json!({
+            "object": "list",
+            "results": [],
+            "next_cursor": null,
+            "has_more": false,
+            "type": "user",
+            "user": {}
+        })
+    }
+
+    fn client(server: &MockServer, retry: RetryPolicy) -> Client {
+        Client::builder("secret")
+            .base_url(server.uri())
+            .rate_limit(None)
+            .retry_policy(retry)
+            .build()
+            .unwrap()
+    }
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 100,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+        };
+
+        for attempt in 0..100 {
+            let delay = policy
+                .delay(
+                    attempt,
+                    &Method::GET,
+                    StatusCode::BAD_GATEWAY,
+                    &HeaderMap::new(),
+                )
+                .unwrap();
+            assert!(delay <= Duration::from_secs(10));
+        }
+
+        assert_eq!(
+            policy.delay(
+                100,
+                &Method::GET,
+                StatusCode::BAD_GATEWAY,
+                &HeaderMap::new()
+            ),
+            None
+        );
+        assert_eq!(
+            policy.delay(0, &Method::GET, StatusCode::NOT_FOUND, &HeaderMap::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn honors_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Retry-After", "2".parse().unwrap());
+
+        let delay =
+            RetryPolicy::default().delay(0, &Method::POST, StatusCode::TOO_MANY_REQUESTS, &headers);
+        assert_eq!(delay, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn only_retries_idempotent_methods_on_errors() {
+        let policy = RetryPolicy::default();
+        let headers = HeaderMap::new();
+
+        for status in [StatusCode::CONFLICT, StatusCode::INTERNAL_SERVER_ERROR] {
+            for method in [Method::GET, Method::PATCH, Method::DELETE] {
+                assert!(policy.delay(0, &method, status, &headers).is_some());
+            }
+            assert_eq!(policy.delay(0, &Method::POST, status, &headers), None);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn token_bucket() {
+        let limiter = RateLimiter::new(RateLimit {
+            requests_per_second: 2.0,
+            burst: 2,
+        });
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+
+        // two from the burst, two more at 2/s
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limited_requests() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/users"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/users"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(users()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let users = client(&server, fast_retries()).list_users().await.unwrap();
+        assert!(users.is_empty());
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/users"))
+            .respond_with(ResponseTemplate::new(503).set_body_json(serde_json::json!({
+                "object": "error",
+                "status": 503,
+                "code": "service_unavailable",
+                "message": "Notion is unavailable."
+            })))
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let err = client(&server, fast_retries())
+            .list_users()
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Some(ErrorCode::ServiceUnavailable));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_failed_creates() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/comments"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let page_id: crate::PageId = "59833787-2cf9-4fdf-8782-e53db20768a5".parse().unwrap();
+        let comment = crate::query::CreateComment::on_page(page_id, "Kale");
+        let err = client(&server, fast_retries())
+            .create_comment(&comment)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_GATEWAY));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_when_disabled() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/users"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, RetryPolicy::disabled());
+        let err = client.list_users().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+}
@@ -1,15 +1,18 @@
 use std::{collections::HashMap, sync::Arc};
 
-use data::parent_object;
 use parking_lot::RwLock;
 
-use crate::{data, data::Page, Client, default};
+use crate::{
+    data,
+    data::{Page, PageOrDatabase, Parent},
+    default, Client, PageId, Result,
+};
 
 pub struct CachedClient {
     /// The pages that are currently cached.
     ///
     /// Map: ID -> Page
-    pages: RwLock<HashMap<String, Arc<Page>>>,
+    pages: RwLock<HashMap<PageId, Arc<Page>>>,
     client: Client,
 }
 
@@ -26,31 +29,43 @@ impl CachedClient {
         &self.client
     }
 
+    /// Search for pages whose title matches `term` and cache them. Databases are left out.
+    ///
     /// # Errors
     /// If the request fails.
-    pub async fn search(&self, term: &str) -> anyhow::Result<Vec<Arc<Page>>> {
-        let response = self.client.search(term).await?;
+    pub async fn search(&self, term: &str) -> Result<Vec<Arc<Page>>> {
+        let request = data::SearchRequest {
+            query: Some(term).filter(|term| !term.is_empty()),
+            filter: Some(data::Filter::pages()),
+            ..default()
+        };
+        let response = self.client.search_with(&request).await?;
         let data::SearchResponse { results, .. } = response;
 
         let mut res = Vec::with_capacity(results.len());
         for result in results {
-            let result = Arc::new(result);
+            let PageOrDatabase::Page(page) = result else {
+                continue;
+            };
+            let result = Arc::new(*page);
             self.pages.write().insert(result.id.clone(), result.clone());
             res.push(result);
         }
         Ok(res)
     }
 
-    pub async fn get_page(&self, id: &str) -> Option<Arc<Page>> {
+    pub async fn get_page(&self, id: &PageId) -> Option<Arc<Page>> {
         // TODO: remove when possible
         // https://www.reddit.com/r/rust/comments/wbacbf/comment/ii5pcby/
         if self.pages.read().contains_key(id) {
             return self.pages.read().get(id).cloned();
         }
 
-        let Ok(page) = self.client.get_page(id).await else { return None };
+        let Ok(page) = self.client.get_page(id).await else {
+            return None;
+        };
 
-        self.pages.write().insert(id.to_string(), page.into());
+        self.pages.write().insert(id.clone(), page.into());
 
         self.pages.read().get(id).cloned()
     }
@@ -63,23 +78,40 @@ impl CachedClient {
     /// Get path of the page as
     ///
     /// `/<parent>/<parent>/<parent>/<page>`
-    pub async fn get_path(&self, id: &str) -> Option<Vec<String>> {
+    ///
+    /// Parents are pages and databases. Blocks in between, e.g. toggles or columns, are skipped.
+    /// The path stops at the first parent that cannot be fetched.
+    pub async fn get_path(&self, id: &PageId) -> Option<Vec<String>> {
         let page = self.get_page(id).await?;
 
         // ids in reverse
-        let mut ids = vec![page.title()?.plain_text.clone()];
+        let mut ids = vec![page.title()];
 
-        let mut page_on = page;
-        while let Some(parent) = page_on.parent.clone() {
-            let parent_object::Data::PageId(parent_id) = parent.data else {
-                return Some(reverse(ids));
+        let mut parent = page.parent.clone();
+        while let Some(next) = parent {
+            parent = match next {
+                Parent::PageId { page_id } => {
+                    let Some(page) = self.get_page(&page_id).await else {
+                        break;
+                    };
+                    ids.push(page.title());
+                    page.parent.clone()
+                }
+                Parent::DatabaseId { database_id } => {
+                    let Ok(database) = self.client.get_database(&database_id).await else {
+                        break;
+                    };
+                    ids.push(database.title());
+                    database.parent
+                }
+                Parent::BlockId { block_id } => {
+                    let Ok(block) = self.client.block(&block_id).await else {
+                        break;
+                    };
+                    block.parent
+                }
+                Parent::Workspace { .. } => None,
             };
-            let Some(parent) = self.get_page(&parent_id).await else {
-                return Some(reverse(ids));
-            };
-            let parent_title = parent.title()?.plain_text.clone();
-            ids.push(parent_title);
-            page_on = parent;
         }
 
         ids.reverse();
@@ -88,22 +120,21 @@ impl CachedClient {
     }
 
     /// Get path of the page similar to `get_path` but a single output string that looks
-    pub async fn get_path_current(&self, id: &str) -> Option<String> {
+    pub async fn get_path_current(&self, id: &PageId) -> Option<String> {
         let path = self.get_path(id).await?;
         path.into_iter().last()
         // let res = path.into_iter().join(" -> ");
     }
 }
 
-/// Reverse the list in place and return it.
-fn reverse(list: Vec<String>) -> Vec<String> {
-    let mut list = list;
-    list.reverse();
-    list
-}
-
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
     use crate::{utils::CachedClient, Client};
 
     fn api() -> CachedClient {
@@ -116,7 +147,122 @@ mod tests {
     #[tokio::test]
     async fn test_get_path() {
         let api = api();
-        let path = api.get_path_current(MEETING_PAGE_ID).await.unwrap();
+        let path = api
+            .get_path_current(&MEETING_PAGE_ID.parse().unwrap())
+            .await
+            .unwrap();
         println!("{path}");
     }
+
+    const USER: &str = "ee5f0f84-409a-440f-983a-a5315961c6e4";
+    const PAGE: &str = "59833787-2cf9-4fdf-8782-e53db20768a5";
+    const COLUMN: &str = "c02fc1d3-db8b-45c5-a222-27595b15aea7";
+    const ROW: &str = "3c612f56-fdd0-4a30-a4d6-bda7d7426309";
+    const DATABASE: &str = "a1d8501e-1ac1-43e9-a6bd-ea9fe6c8822b";
+
+    fn title(text: &str) -> serde_json::Value {
+        json!([{
+            "type": "text",
+            "text": { "content": text, "link": null },
+            "annotations": {
+                "bold": false, "italic": false, "strikethrough": false,
+                "underline": false, "code": false, "color": "default"
+            },
+            "plain_text": text,
+            "href": null
+        }])
+    }
+
+    fn object(object: &str, id: &str, parent: serde_json::Value) -> serde_json::Value {
+        json!({
+            "object": object,
+            "id": id,
+            "parent": parent,
+            "created_time": "2023-03-13T03:32:00.000Z",
+            "created_by": { "object": "user", "id": USER },
+            "last_edited_time": "2023-03-13T03:32:00.000Z",
+            "last_edited_by": { "object": "user", "id": USER },
+            "archived": false
+        })
+    }
+
+    async fn mount(
+        server: &MockServer,
+        route: String,
+        mut body: serde_json::Value,
+        extra: serde_json::Value,
+    ) {
+        body.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn get_path_walks_blocks_and_databases() {
+        let server = MockServer::start().await;
+
+        // page -> column block -> database row -> database -> workspace
+        let page = object(
+            "page",
+            PAGE,
+            json!({ "type": "block_id", "block_id": COLUMN }),
+        );
+        let properties = json!({
+            "url": "https://www.notion.so/page",
+            "cover": null,
+            "icon": null,
+            "properties": { "title": { "id": "title", "type": "title", "title": title("Tuscan kale") } }
+        });
+        mount(&server, format!("/pages/{PAGE}"), page, properties).await;
+
+        let column = object(
+            "block",
+            COLUMN,
+            json!({ "type": "page_id", "page_id": ROW }),
+        );
+        let data = json!({ "type": "column", "column": {}, "has_children": true });
+        mount(&server, format!("/blocks/{COLUMN}"), column, data).await;
+
+        let row = object(
+            "page",
+            ROW,
+            json!({ "type": "database_id", "database_id": DATABASE }),
+        );
+        let properties = json!({
+            "url": "https://www.notion.so/row",
+            "cover": null,
+            "icon": null,
+            "properties": { "Name": { "id": "title", "type": "title", "title": title("Vegetables") } }
+        });
+        mount(&server, format!("/pages/{ROW}"), row, properties).await;
+
+        let database = object(
+            "database",
+            DATABASE,
+            json!({ "type": "workspace", "workspace": true }),
+        );
+        let schema = json!({
+            "url": "https://www.notion.so/database",
+            "title": title("Groceries"),
+            "cover": null,
+            "icon": null,
+            "properties": {}
+        });
+        mount(&server, format!("/databases/{DATABASE}"), database, schema).await;
+
+        let client = Client::builder("secret")
+            .base_url(server.uri())
+            .rate_limit(None)
+            .build()
+            .unwrap();
+        let api = CachedClient::new(client);
+
+        let path = api.get_path(&PAGE.parse().unwrap()).await.unwrap();
+        assert_eq!(path, ["Groceries", "Vegetables", "Tuscan kale"]);
+    }
 }